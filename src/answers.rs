@@ -0,0 +1,206 @@
+/*
+    The expected answers of the real inputs, stored in a small TOML file
+    so a refactor that breaks a day is caught by the runner:
+
+    [day_01]
+    part_1 = 1234
+    part_2 = 5678
+
+    [day_17]
+    part_1 = "4,6,3,5,6,3,5,2,1,0"
+
+    A part without an expected answer is simply unknown, so the file
+    can be filled as the days are solved, or with `--record`.
+*/
+
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    path::{Path, PathBuf},
+};
+
+use crate::answer::Answer;
+
+#[derive(Debug, Default)]
+pub struct Answers {
+    days: BTreeMap<u8, [Option<Answer>; 2]>,
+}
+
+/// How a part answer compares to the expected one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail(Answer),
+    Unknown,
+}
+
+#[derive(Debug)]
+pub enum AnswersError {
+    Io(PathBuf, std::io::Error),
+    Parse(PathBuf, String),
+}
+
+impl Display for AnswersError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            AnswersError::Io(path, error) => {
+                write!(f, "failed to access {}: {}", path.display(), error)
+            }
+            AnswersError::Parse(path, error) => {
+                write!(f, "invalid answers file {}: {}", path.display(), error)
+            }
+        }
+    }
+}
+
+fn parse_day_key(key: &str) -> Option<u8> {
+    key.strip_prefix("day_")?.parse().ok()
+}
+
+fn parse_part_key(key: &str) -> Option<usize> {
+    match key {
+        "part_1" => Some(0),
+        "part_2" => Some(1),
+        _ => None,
+    }
+}
+
+impl Answers {
+    /// Loads the answers file, a missing file means no answer is known yet.
+    pub fn load(path: &Path) -> Result<Self, AnswersError> {
+        match std::fs::read_to_string(path) {
+            Ok(data) => Answers::parse(&data)
+                .map_err(|error| AnswersError::Parse(path.to_path_buf(), error)),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(Answers::default()),
+            Err(error) => Err(AnswersError::Io(path.to_path_buf(), error)),
+        }
+    }
+
+    fn parse(data: &str) -> Result<Self, String> {
+        let table: toml::Table =
+            toml::from_str(data).map_err(|error| error.message().to_string())?;
+        let mut answers = Answers::default();
+        for (day_key, parts) in table {
+            let day = parse_day_key(&day_key)
+                .ok_or_else(|| format!("`{}` is not a day, expected `day_XX`", day_key))?;
+            let parts = parts
+                .as_table()
+                .ok_or_else(|| format!("`{}` must be a table", day_key))?;
+            let entry = answers.days.entry(day).or_default();
+            for (part_key, value) in parts {
+                let part = parse_part_key(part_key).ok_or_else(|| {
+                    format!(
+                        "`{}.{}` is not a part, expected `part_1` or `part_2`",
+                        day_key, part_key
+                    )
+                })?;
+                entry[part] = Some(match value {
+                    toml::Value::Integer(value) => Answer::Integer(*value),
+                    toml::Value::String(value) => Answer::Text(value.clone()),
+                    _ => {
+                        return Err(format!(
+                            "`{}.{}` must be an integer or a string",
+                            day_key, part_key
+                        ))
+                    }
+                });
+            }
+        }
+        Ok(answers)
+    }
+
+    pub fn expected(&self, day: u8, part: u8) -> Option<&Answer> {
+        self.days.get(&day)?[part as usize - 1].as_ref()
+    }
+
+    /// Answers are compared by their text, the file doesn't know
+    /// whether a number was signed or a piece of text.
+    pub fn verify(&self, day: u8, part: u8, answer: &Answer) -> Verdict {
+        match self.expected(day, part) {
+            Some(expected) if expected.to_string() == answer.to_string() => Verdict::Pass,
+            Some(expected) => Verdict::Fail(expected.clone()),
+            None => Verdict::Unknown,
+        }
+    }
+
+    pub fn record(&mut self, day: u8, part: u8, answer: Answer) {
+        self.days.entry(day).or_default()[part as usize - 1] = Some(answer);
+    }
+
+    fn to_toml(&self) -> String {
+        let mut table = toml::Table::new();
+        for (day, parts) in &self.days {
+            let mut parts_table = toml::Table::new();
+            for (index, answer) in parts.iter().enumerate() {
+                let value = match answer {
+                    Some(Answer::Integer(value)) => toml::Value::Integer(*value),
+                    Some(Answer::Unsigned(value)) => match i64::try_from(*value) {
+                        Ok(value) => toml::Value::Integer(value),
+                        Err(_) => toml::Value::String(value.to_string()),
+                    },
+                    Some(Answer::Text(value)) => toml::Value::String(value.clone()),
+                    None => continue,
+                };
+                parts_table.insert(format!("part_{}", index + 1), value);
+            }
+            if !parts_table.is_empty() {
+                table.insert(format!("day_{:02}", day), toml::Value::Table(parts_table));
+            }
+        }
+        toml::to_string(&table).expect("Failed to serialize the answers")
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), AnswersError> {
+        std::fs::write(path, self.to_toml())
+            .map_err(|error| AnswersError::Io(path.to_path_buf(), error))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "[day_01]
+part_1 = 11
+part_2 = 31
+
+[day_17]
+part_1 = \"4,6,3,5,6,3,5,2,1,0\"
+";
+
+    #[test]
+    fn test_verify_answers() {
+        let answers = Answers::parse(EXAMPLE).unwrap();
+        assert_eq!(answers.verify(1, 1, &Answer::Integer(11)), Verdict::Pass);
+        assert_eq!(answers.verify(1, 2, &Answer::Unsigned(31)), Verdict::Pass);
+        assert_eq!(
+            answers.verify(1, 2, &Answer::Integer(32)),
+            Verdict::Fail(Answer::Integer(31))
+        );
+        assert_eq!(
+            answers.verify(17, 1, &Answer::Text("4,6,3,5,6,3,5,2,1,0".to_string())),
+            Verdict::Pass
+        );
+        assert_eq!(
+            answers.verify(17, 2, &Answer::Integer(42)),
+            Verdict::Unknown
+        );
+        assert_eq!(answers.verify(5, 1, &Answer::Integer(42)), Verdict::Unknown);
+    }
+
+    #[test]
+    fn test_record_answers() {
+        let mut answers = Answers::default();
+        answers.record(17, 1, Answer::Text("4,6,3,5,6,3,5,2,1,0".to_string()));
+        answers.record(1, 2, Answer::Unsigned(31));
+        answers.record(1, 1, Answer::Integer(11));
+        assert_eq!(answers.to_toml(), EXAMPLE);
+    }
+
+    #[test]
+    fn test_invalid_answers() {
+        assert!(Answers::parse("[day_01]\npart_3 = 1\n").is_err());
+        assert!(Answers::parse("[first]\npart_1 = 1\n").is_err());
+        assert!(Answers::parse("[day_01]\npart_1 = 1.5\n").is_err());
+    }
+}
@@ -0,0 +1,302 @@
+/*
+    Benchmarks of the days, every step (parsing and each part) is run
+    a few times after a warm-up, and the median is compared to a saved
+    baseline to spot the refactors that made a day slower:
+
+    [day_16]
+    parse_ns = 152300
+    part_1_ns = 8410200
+    part_2_ns = 9120800
+*/
+
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use crate::{params::Params, runner::catch_panic, solution::DynSolution};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Step {
+    Parse,
+    Part(u8),
+}
+
+impl Step {
+    fn key(&self) -> String {
+        match self {
+            Step::Parse => "parse_ns".to_string(),
+            Step::Part(part) => format!("part_{}_ns", part),
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Step> {
+        match key {
+            "parse_ns" => Some(Step::Parse),
+            "part_1_ns" => Some(Step::Part(1)),
+            "part_2_ns" => Some(Step::Part(2)),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Step {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Step::Parse => write!(f, "parse"),
+            Step::Part(part) => write!(f, "part {}", part),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &[Duration]) -> Self {
+        assert!(!samples.is_empty(), "No samples to compute statistics on");
+        let mut samples = samples.to_vec();
+        samples.sort();
+        let count = samples.len();
+
+        let median = if count % 2 == 1 {
+            samples[count / 2]
+        } else {
+            (samples[count / 2 - 1] + samples[count / 2]) / 2
+        };
+
+        let nanos = samples.iter().map(|sample| sample.as_nanos() as f64);
+        let mean = nanos.clone().sum::<f64>() / count as f64;
+        // Sample standard deviation, a single run has none.
+        let variance = if count > 1 {
+            nanos.map(|value| (value - mean).powi(2)).sum::<f64>() / (count - 1) as f64
+        } else {
+            0.0
+        };
+
+        Stats {
+            min: samples[0],
+            median,
+            mean: Duration::from_nanos(mean.round() as u64),
+            stddev: Duration::from_nanos(variance.sqrt().round() as u64),
+        }
+    }
+}
+
+/// Change of the median compared to the baseline, in percent.
+pub fn change_percent(baseline: Duration, median: Duration) -> f64 {
+    (median.as_nanos() as f64 - baseline.as_nanos() as f64) / baseline.as_nanos().max(1) as f64
+        * 100.0
+}
+
+/// The saved medians of a previous benchmark.
+#[derive(Debug, Default)]
+pub struct Baseline {
+    days: BTreeMap<u8, BTreeMap<Step, Duration>>,
+}
+
+#[derive(Debug)]
+pub enum BaselineError {
+    Io(PathBuf, std::io::Error),
+    Parse(PathBuf, String),
+}
+
+impl Display for BaselineError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            BaselineError::Io(path, error) => {
+                write!(f, "failed to access {}: {}", path.display(), error)
+            }
+            BaselineError::Parse(path, error) => {
+                write!(f, "invalid baseline file {}: {}", path.display(), error)
+            }
+        }
+    }
+}
+
+impl Baseline {
+    /// Loads the baseline file, a missing file is an empty baseline.
+    pub fn load(path: &Path) -> Result<Self, BaselineError> {
+        match std::fs::read_to_string(path) {
+            Ok(data) => Baseline::parse(&data)
+                .map_err(|error| BaselineError::Parse(path.to_path_buf(), error)),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(Baseline::default()),
+            Err(error) => Err(BaselineError::Io(path.to_path_buf(), error)),
+        }
+    }
+
+    fn parse(data: &str) -> Result<Self, String> {
+        let table: toml::Table =
+            toml::from_str(data).map_err(|error| error.message().to_string())?;
+        let mut baseline = Baseline::default();
+        for (day_key, steps) in table {
+            let day = day_key
+                .strip_prefix("day_")
+                .and_then(|day| day.parse::<u8>().ok())
+                .ok_or_else(|| format!("`{}` is not a day, expected `day_XX`", day_key))?;
+            let steps = steps
+                .as_table()
+                .ok_or_else(|| format!("`{}` must be a table", day_key))?;
+            let entry = baseline.days.entry(day).or_default();
+            for (step_key, value) in steps {
+                let step = Step::from_key(step_key).ok_or_else(|| {
+                    format!(
+                        "`{}.{}` is not a step, expected `parse_ns`, `part_1_ns` or `part_2_ns`",
+                        day_key, step_key
+                    )
+                })?;
+                let nanos = value
+                    .as_integer()
+                    .and_then(|nanos| u64::try_from(nanos).ok())
+                    .ok_or_else(|| {
+                        format!(
+                            "`{}.{}` must be a positive number of nanoseconds",
+                            day_key, step_key
+                        )
+                    })?;
+                entry.insert(step, Duration::from_nanos(nanos));
+            }
+        }
+        Ok(baseline)
+    }
+
+    pub fn get(&self, day: u8, step: Step) -> Option<Duration> {
+        self.days.get(&day)?.get(&step).copied()
+    }
+
+    pub fn set(&mut self, day: u8, step: Step, median: Duration) {
+        self.days.entry(day).or_default().insert(step, median);
+    }
+
+    fn to_toml(&self) -> String {
+        let mut table = toml::Table::new();
+        for (day, steps) in &self.days {
+            let steps_table = steps
+                .iter()
+                .map(|(step, median)| {
+                    (
+                        step.key(),
+                        toml::Value::Integer(median.as_nanos().min(i64::MAX as u128) as i64),
+                    )
+                })
+                .collect::<toml::Table>();
+            table.insert(format!("day_{:02}", day), toml::Value::Table(steps_table));
+        }
+        toml::to_string(&table).expect("Failed to serialize the baseline")
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), BaselineError> {
+        std::fs::write(path, self.to_toml())
+            .map_err(|error| BaselineError::Io(path.to_path_buf(), error))
+    }
+}
+
+/// Times every run of a step, stopping at the first error or panic.
+fn sample<T, E: Display>(
+    runs: u32,
+    mut step: impl FnMut() -> Result<T, E>,
+) -> Result<Vec<Duration>, String> {
+    (0..runs)
+        .map(|_| {
+            let (output, elapsed) = catch_panic(|| {
+                let now = Instant::now();
+                let output = step();
+                (output, now.elapsed())
+            })
+            .map_err(|message| format!("panicked: {}", message))?;
+            output.map_err(|error| error.to_string())?;
+            Ok(elapsed)
+        })
+        .collect()
+}
+
+/// Measures the parsing and the selected parts of a day. Each step is
+/// warmed up then sampled on its own, the parts reuse a single parsing.
+pub fn bench_day(
+    day: &dyn DynSolution,
+    data: &str,
+    parts: &[u8],
+    warmup: u32,
+    runs: u32,
+    params: &Params,
+) -> Vec<(Step, Result<Stats, String>)> {
+    let parse = || day.parse(data);
+    let mut results = vec![(
+        Step::Parse,
+        sample(warmup, parse)
+            .and_then(|_| sample(runs, parse))
+            .map(|samples| Stats::from_samples(&samples)),
+    )];
+
+    let input = match catch_panic(parse) {
+        Ok(Ok(input)) => input,
+        _ => return results,
+    };
+    for &part in parts {
+        let stats = sample(warmup, || day.solve(part, input.as_ref(), params))
+            .and_then(|_| sample(runs, || day.solve(part, input.as_ref(), params)))
+            .map(|samples| Stats::from_samples(&samples));
+        results.push((Step::Part(part), stats));
+    }
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats() {
+        let samples = [7, 3, 5, 1, 4].map(Duration::from_nanos);
+        let stats = Stats::from_samples(&samples);
+        assert_eq!(stats.min, Duration::from_nanos(1));
+        assert_eq!(stats.median, Duration::from_nanos(4));
+        assert_eq!(stats.mean, Duration::from_nanos(4));
+        assert_eq!(stats.stddev, Duration::from_nanos(2));
+
+        let samples = [10, 20].map(Duration::from_nanos);
+        assert_eq!(
+            Stats::from_samples(&samples).median,
+            Duration::from_nanos(15)
+        );
+    }
+
+    #[test]
+    fn test_change_percent() {
+        assert_eq!(
+            change_percent(Duration::from_nanos(100), Duration::from_nanos(125)),
+            25.0
+        );
+        assert_eq!(
+            change_percent(Duration::from_nanos(200), Duration::from_nanos(150)),
+            -25.0
+        );
+    }
+
+    #[test]
+    fn test_baseline_round_trip() {
+        let mut baseline = Baseline::default();
+        baseline.set(16, Step::Part(2), Duration::from_nanos(9120800));
+        baseline.set(16, Step::Parse, Duration::from_nanos(152300));
+        baseline.set(3, Step::Part(1), Duration::from_nanos(42));
+        let data = baseline.to_toml();
+        assert_eq!(
+            data,
+            "[day_03]\npart_1_ns = 42\n\n[day_16]\nparse_ns = 152300\npart_2_ns = 9120800\n"
+        );
+        let baseline = Baseline::parse(&data).unwrap();
+        assert_eq!(
+            baseline.get(16, Step::Parse),
+            Some(Duration::from_nanos(152300))
+        );
+        assert_eq!(baseline.get(16, Step::Part(1)), None);
+        assert!(Baseline::parse("[day_03]\npart_3_ns = 42\n").is_err());
+    }
+}
@@ -0,0 +1,859 @@
+/*
+    The chronospatial computer of day 17, on its own so it can be used
+    outside of the day: to run a program, to step through it and look at
+    the registers after each instruction, or to print it as something
+    readable instead of a list of numbers.
+
+    The instructions are decoded before they run, an invalid opcode or
+    operand is an error and not a panic. The number of instructions is
+    limited by a budget, 100 million by default, because a program can
+    loop forever.
+*/
+
+use std::fmt::Display;
+
+use crate::solution::SolveError;
+
+/// The number of instructions a program runs before it's considered stuck.
+pub const DEFAULT_BUDGET: u64 = 100_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Opcode {
+    Adv,
+    Bxl,
+    Bst,
+    Jnz,
+    Bxc,
+    Out,
+    Bdv,
+    Cdv,
+}
+
+impl Opcode {
+    pub fn from_u8(opcode: u8) -> Option<Opcode> {
+        match opcode {
+            0 => Some(Opcode::Adv),
+            1 => Some(Opcode::Bxl),
+            2 => Some(Opcode::Bst),
+            3 => Some(Opcode::Jnz),
+            4 => Some(Opcode::Bxc),
+            5 => Some(Opcode::Out),
+            6 => Some(Opcode::Bdv),
+            7 => Some(Opcode::Cdv),
+            _ => None,
+        }
+    }
+
+    pub fn mnemonic(self) -> &'static str {
+        match self {
+            Opcode::Adv => "adv",
+            Opcode::Bxl => "bxl",
+            Opcode::Bst => "bst",
+            Opcode::Jnz => "jnz",
+            Opcode::Bxc => "bxc",
+            Opcode::Out => "out",
+            Opcode::Bdv => "bdv",
+            Opcode::Cdv => "cdv",
+        }
+    }
+
+    /// Whether the operand is a combo operand, which can read a register.
+    pub fn has_combo_operand(self) -> bool {
+        matches!(
+            self,
+            Opcode::Adv | Opcode::Bst | Opcode::Out | Opcode::Bdv | Opcode::Cdv
+        )
+    }
+}
+
+/// A decoded instruction, with its position in the program.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Instruction {
+    pub position: usize,
+    pub opcode: Opcode,
+    pub operand: u8,
+}
+
+impl Instruction {
+    /// Decodes the instruction at `position`, `None` when the program halts
+    /// there (an opcode without its operand halts it too).
+    pub fn decode(program: &[u8], position: usize) -> Result<Option<Instruction>, SolveError> {
+        if position + 1 >= program.len() {
+            return Ok(None);
+        }
+        let opcode = Opcode::from_u8(program[position]).ok_or(SolveError::InvalidOpcode {
+            opcode: program[position],
+            position,
+        })?;
+        let operand = program[position + 1];
+        // The combo operand 7 is reserved, the literal ones are 3 bits.
+        if operand > 7 || (opcode.has_combo_operand() && operand == 7) {
+            return Err(SolveError::InvalidOperand {
+                operand,
+                position: position + 1,
+            });
+        }
+        Ok(Some(Instruction {
+            position,
+            opcode,
+            operand,
+        }))
+    }
+
+    /// The operand as it reads in the disassembly, `a`, `b` and `c` for
+    /// the combo operands reading a register.
+    fn operand_name(&self) -> String {
+        match (self.opcode.has_combo_operand(), self.operand) {
+            (true, 4) => "a".to_string(),
+            (true, 5) => "b".to_string(),
+            (true, 6) => "c".to_string(),
+            (_, operand) => operand.to_string(),
+        }
+    }
+
+    /// What the instruction does, written like an assignment.
+    pub fn effect(&self) -> String {
+        let operand = self.operand_name();
+        match self.opcode {
+            Opcode::Adv => format!("a = a >> {}", operand),
+            Opcode::Bxl => format!("b = b ^ {}", operand),
+            Opcode::Bst => format!("b = {} % 8", operand),
+            Opcode::Jnz => format!("if a != 0 goto {}", operand),
+            Opcode::Bxc => "b = b ^ c".to_string(),
+            Opcode::Out => format!("output {} % 8", operand),
+            Opcode::Bdv => format!("b = a >> {}", operand),
+            Opcode::Cdv => format!("c = a >> {}", operand),
+        }
+    }
+}
+
+impl Display for Instruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{:>3}: {} {:<3} ; {}",
+            self.position,
+            self.opcode.mnemonic(),
+            self.operand_name(),
+            self.effect()
+        )
+    }
+}
+
+/// Decodes the whole program, instruction by instruction from the start.
+/// Jumps to odd positions are possible, but no program I've seen does it.
+pub fn disassemble(program: &[u8]) -> Result<Vec<Instruction>, SolveError> {
+    let mut instructions = Vec::with_capacity(program.len() / 2);
+    let mut position = 0;
+    while let Some(instruction) = Instruction::decode(program, position)? {
+        instructions.push(instruction);
+        position += 2;
+    }
+    Ok(instructions)
+}
+
+/// The disassembly of the program, an instruction per line.
+pub fn disassembly(program: &[u8]) -> Result<String, SolveError> {
+    Ok(disassemble(program)?
+        .iter()
+        .map(|instruction| format!("{}\n", instruction))
+        .collect())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Register {
+    A,
+    B,
+    C,
+}
+
+impl Instruction {
+    /// The registers the instruction reads, the combo operand included.
+    pub fn reads(&self) -> Vec<Register> {
+        let mut registers = match self.opcode {
+            Opcode::Adv | Opcode::Bdv | Opcode::Cdv | Opcode::Jnz => vec![Register::A],
+            Opcode::Bxl => vec![Register::B],
+            Opcode::Bxc => vec![Register::B, Register::C],
+            Opcode::Bst | Opcode::Out => vec![],
+        };
+        if self.opcode.has_combo_operand() {
+            match self.operand {
+                4 => registers.push(Register::A),
+                5 => registers.push(Register::B),
+                6 => registers.push(Register::C),
+                _ => {}
+            }
+        }
+        registers
+    }
+
+    /// The register the instruction writes, if any.
+    pub fn writes(&self) -> Option<Register> {
+        match self.opcode {
+            Opcode::Adv => Some(Register::A),
+            Opcode::Bxl | Opcode::Bst | Opcode::Bxc | Opcode::Bdv => Some(Register::B),
+            Opcode::Cdv => Some(Register::C),
+            Opcode::Jnz | Opcode::Out => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Registers {
+    pub a: i64,
+    pub b: i64,
+    pub c: i64,
+}
+
+/// An executed instruction, with the registers after it and what it wrote.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Step {
+    pub instruction: Instruction,
+    pub registers: Registers,
+    pub output: Option<u8>,
+}
+
+impl Display for Step {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{:<32} a={} b={} c={}",
+            self.instruction.to_string(),
+            self.registers.a,
+            self.registers.b,
+            self.registers.c
+        )?;
+        if let Some(value) = self.output {
+            write!(f, " out={}", value)?;
+        }
+        Ok(())
+    }
+}
+
+/// The chronospatial computer: three registers, the program
+/// and what it has written so far.
+#[derive(Debug, Clone)]
+pub struct Program {
+    pub register_a: i64,
+    pub register_b: i64,
+    pub register_c: i64,
+
+    pub program: Vec<u8>,
+
+    pub output: Vec<u8>,
+
+    pub instruction_pointer: usize,
+    /// The instructions run since the last reset.
+    pub instructions: u64,
+    /// The instructions the program can run before it fails.
+    pub budget: u64,
+}
+
+impl Program {
+    pub fn new(register_a: i64, register_b: i64, register_c: i64, program: Vec<u8>) -> Self {
+        Program {
+            register_a,
+            register_b,
+            register_c,
+            program,
+            output: Vec::new(),
+            instruction_pointer: 0,
+            instructions: 0,
+            budget: DEFAULT_BUDGET,
+        }
+    }
+
+    pub fn with_budget(mut self, budget: u64) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    pub fn registers(&self) -> Registers {
+        Registers {
+            a: self.register_a,
+            b: self.register_b,
+            c: self.register_c,
+        }
+    }
+
+    fn write(&mut self, value: u8) {
+        self.output.push(value);
+    }
+
+    /// Runs the next instruction, `None` once the program has halted.
+    pub fn step(&mut self) -> Result<Option<Step>, SolveError> {
+        let Some(instruction) = Instruction::decode(&self.program, self.instruction_pointer)?
+        else {
+            return Ok(None);
+        };
+        if self.instructions >= self.budget {
+            return Err(SolveError::InfiniteLoop {
+                instructions: self.budget,
+            });
+        }
+        self.instructions += 1;
+
+        let written = self.output.len();
+        let operand = instruction.operand;
+        self.instruction_pointer += 2;
+        match instruction.opcode {
+            Opcode::Adv => execute_adv(self, operand),
+            Opcode::Bxl => execute_bxl(self, operand),
+            Opcode::Bst => execute_bst(self, operand),
+            Opcode::Jnz => {
+                if let Some(jump) = execute_jnz(self, operand) {
+                    self.instruction_pointer = jump;
+                }
+            }
+            Opcode::Bxc => execute_bxc(self, operand),
+            Opcode::Out => execute_out(self, operand),
+            Opcode::Bdv => execute_bdv(self, operand),
+            Opcode::Cdv => execute_cdv(self, operand),
+        }
+
+        Ok(Some(Step {
+            instruction,
+            registers: self.registers(),
+            output: self.output.get(written).copied(),
+        }))
+    }
+
+    /// The steps of the program until it halts, or until the first error
+    /// which is the last item.
+    pub fn trace(&mut self) -> impl Iterator<Item = Result<Step, SolveError>> + '_ {
+        let mut failed = false;
+        std::iter::from_fn(move || {
+            if failed {
+                return None;
+            }
+            let step = self.step().transpose();
+            failed = matches!(step, Some(Err(_)));
+            step
+        })
+    }
+
+    /// Runs the program until it halts, fails on an invalid instruction
+    /// or once the budget is spent.
+    pub fn execute(&mut self) -> Result<(), SolveError> {
+        while self.step()?.is_some() {}
+        Ok(())
+    }
+
+    /// Clears the registers and the output, and goes back to the start of
+    /// the program. The program and the budget are kept.
+    pub fn reset(&mut self) {
+        self.register_a = 0;
+        self.register_b = 0;
+        self.register_c = 0;
+        self.output.clear();
+        self.instruction_pointer = 0;
+        self.instructions = 0;
+    }
+}
+
+fn literal_operand(operand: u8) -> i64 {
+    operand as i64
+}
+
+fn combo_operand(program: &Program, operand: u8) -> i64 {
+    match operand {
+        0..=3 => operand as i64,
+        4 => program.register_a,
+        5 => program.register_b,
+        6 => program.register_c,
+        _ => unreachable!("The operands are checked when decoding"),
+    }
+}
+
+/// The division of the adv, bdv and cdv instructions. The shift can be a
+/// register with any value, beyond 63 nothing is left.
+fn divide(program: &Program, operand: u8) -> i64 {
+    let shift = combo_operand(program, operand);
+    if !(0..63).contains(&shift) {
+        0
+    } else {
+        program.register_a / 2_i64.pow(shift as u32)
+    }
+}
+
+/**
+ * The adv instruction (opcode 0) performs division. The numerator is the value
+ * in the A register. The denominator is found by raising 2 to the power of the
+ * instruction's combo operand. (So, an operand of 2 would divide A by 4 (2^2);
+ * an operand of 5 would divide A by 2^B.) The result of the division operation
+ * is truncated to an integer and then written to the A register.
+ */
+fn execute_adv(program: &mut Program, operand: u8) {
+    program.register_a = divide(program, operand);
+}
+
+/**
+ * The bxl instruction (opcode 1) calculates the bitwise XOR of register B and
+ * the instruction's literal operand, then stores the result in register B.
+ */
+fn execute_bxl(program: &mut Program, operand: u8) {
+    let result = program.register_b ^ literal_operand(operand);
+    program.register_b = result;
+}
+
+/**
+ * The bst instruction (opcode 2) calculates the value of its combo operand modulo 8
+ * (thereby keeping only its lowest 3 bits), then writes that value to the B register.
+ */
+fn execute_bst(program: &mut Program, operand: u8) {
+    let result = combo_operand(program, operand) & 0b111;
+    program.register_b = result;
+}
+
+/**
+ * The jnz instruction (opcode 3) does nothing if the A register is 0. However,
+ * if the A register is not zero, it jumps by setting the instruction pointer to the
+ * value of its literal operand; if this instruction jumps, the instruction pointer
+ * is not increased by 2 after this instruction.
+ */
+fn execute_jnz(program: &mut Program, operand: u8) -> Option<usize> {
+    if program.register_a != 0 {
+        return Some(literal_operand(operand) as usize);
+    }
+    None
+}
+
+/**
+ * The bxc instruction (opcode 4) calculates the bitwise XOR of register B and
+ * register C, then stores the result in register B. (For legacy reasons, this
+ * instruction reads an operand but ignores it.)
+ */
+fn execute_bxc(program: &mut Program, _operand: u8) {
+    let result = program.register_b ^ program.register_c;
+    program.register_b = result;
+}
+
+/**
+ * The out instruction (opcode 5) calculates the value of its combo operand modulo 8,
+ * then outputs that value. (If a program outputs multiple values, they are
+ * separated by commas.)
+ */
+fn execute_out(program: &mut Program, operand: u8) {
+    let result = (combo_operand(program, operand) & 0b111) as u8;
+    program.write(result);
+}
+
+/**
+ * The bdv instruction (opcode 6) works exactly like the adv instruction except that
+ * the result is stored in the B register. (The numerator is still read from the A register.)
+ */
+fn execute_bdv(program: &mut Program, operand: u8) {
+    program.register_b = divide(program, operand);
+}
+
+/**
+ * The cdv instruction (opcode 7) works exactly like the adv instruction except that
+ * the result is stored in the C register. (The numerator is still read from the A register.)
+ */
+fn execute_cdv(program: &mut Program, operand: u8) {
+    program.register_c = divide(program, operand);
+}
+
+/// The loop of a program that consumes A a few bits at a time, like the
+/// ones of the puzzle inputs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Loop {
+    /// Position of the first instruction of the loop, where the jnz goes.
+    pub start: usize,
+    /// Position of the jnz closing the loop.
+    pub end: usize,
+    /// The number of bits A is shifted right by at each iteration.
+    pub shift: u8,
+    /// The number of values written by each iteration.
+    pub outputs: usize,
+}
+
+/// Finds the loop of the program in its disassembly, and checks that each
+/// iteration only depends on the value of A at its start:
+///
+/// - a single jnz, jumping backwards, closes the loop;
+/// - A is only written by one adv in the loop, shifting it by a constant;
+/// - B and C are written before being read in the loop;
+/// - outside the loop, nothing is output and A isn't written. B and C
+///   can be, the loop writes them before reading them anyway.
+///
+/// The error says which of these the program breaks.
+pub fn analyze_loop(program: &[u8]) -> Result<Loop, SolveError> {
+    let instructions = disassemble(program)?;
+
+    let jumps = instructions
+        .iter()
+        .filter(|instruction| instruction.opcode == Opcode::Jnz)
+        .collect::<Vec<_>>();
+    let jump = match jumps[..] {
+        [jump] => jump,
+        [] => {
+            return Err(SolveError::Unsupported(
+                "the program has no jnz instruction, so no loop".to_string(),
+            ))
+        }
+        _ => {
+            return Err(SolveError::Unsupported(format!(
+                "the program has {} jnz instructions, only a single loop is supported",
+                jumps.len()
+            )))
+        }
+    };
+    let start = jump.operand as usize;
+    if !start.is_multiple_of(2) || start > jump.position {
+        return Err(SolveError::Unsupported(format!(
+            "the jnz at position {} doesn't jump backwards to an instruction",
+            jump.position
+        )));
+    }
+
+    let mut shift = None;
+    let mut outputs = 0;
+    let mut written = Vec::new();
+    for instruction in &instructions {
+        let in_loop = (start..jump.position).contains(&instruction.position);
+        if !in_loop {
+            if instruction.opcode == Opcode::Out {
+                return Err(SolveError::Unsupported(format!(
+                    "the out at position {} is outside of the loop",
+                    instruction.position
+                )));
+            }
+            if instruction.writes() == Some(Register::A) {
+                return Err(SolveError::Unsupported(format!(
+                    "A is written at position {}, outside of the loop",
+                    instruction.position
+                )));
+            }
+            continue;
+        }
+
+        for register in instruction.reads() {
+            if register != Register::A && !written.contains(&register) {
+                return Err(SolveError::Unsupported(format!(
+                    "{:?} is read at position {} before being written in the loop, \
+                     so an iteration depends on the previous one",
+                    register, instruction.position
+                )));
+            }
+        }
+        match instruction.writes() {
+            Some(Register::A) => {
+                if shift.is_some() {
+                    return Err(SolveError::Unsupported(
+                        "A is shifted more than once in the loop".to_string(),
+                    ));
+                }
+                if !(1..=3).contains(&instruction.operand) {
+                    return Err(SolveError::Unsupported(format!(
+                        "the adv at position {} doesn't shift A by a constant number of bits",
+                        instruction.position
+                    )));
+                }
+                shift = Some(instruction.operand);
+            }
+            Some(register) => written.push(register),
+            None => {}
+        }
+        if instruction.opcode == Opcode::Out {
+            outputs += 1;
+        }
+    }
+
+    let Some(shift) = shift else {
+        return Err(SolveError::Unsupported(
+            "A isn't shifted in the loop, so the loop never ends".to_string(),
+        ));
+    };
+    if outputs == 0 {
+        return Err(SolveError::Unsupported(
+            "the loop doesn't output anything".to_string(),
+        ));
+    }
+
+    Ok(Loop {
+        start,
+        end: jump.position,
+        shift,
+        outputs,
+    })
+}
+
+/// The lowest initial value of A for which the program writes `expected`.
+///
+/// The program must have the loop [`analyze_loop`] looks for. The last
+/// iteration only sees the highest bits of A, so the search goes backwards
+/// from it, trying the `shift` bits below the ones already found.
+pub fn lowest_register_a(program: &Program, expected: &[u8]) -> Result<i64, SolveError> {
+    let shape = analyze_loop(&program.program)?;
+    if !expected.len().is_multiple_of(shape.outputs) {
+        return Err(SolveError::Unsupported(format!(
+            "each iteration writes {} values, which doesn't divide the {} expected values",
+            shape.outputs,
+            expected.len()
+        )));
+    }
+    let iterations = expected.len() / shape.outputs;
+    if iterations == 0 {
+        return Err(SolveError::NoSolution);
+    }
+    if iterations * shape.shift as usize > 63 {
+        return Err(SolveError::Unsupported(format!(
+            "A would need {} bits for {} iterations",
+            iterations * shape.shift as usize,
+            iterations
+        )));
+    }
+
+    let mut computer = program.clone();
+    search_register_a(
+        &mut computer,
+        &shape,
+        expected,
+        iterations,
+        iterations - 1,
+        0,
+    )?
+    .ok_or(SolveError::NoSolution)
+}
+
+fn search_register_a(
+    computer: &mut Program,
+    shape: &Loop,
+    expected: &[u8],
+    iterations: usize,
+    iteration: usize,
+    high: i64,
+) -> Result<Option<i64>, SolveError> {
+    let wanted = &expected[iteration * shape.outputs..(iteration + 1) * shape.outputs];
+    for low in 0..(1 << shape.shift) {
+        let a = (high << shape.shift) | low;
+        // A at zero would end the loop before the last iteration
+        if a == 0 && iterations > 1 {
+            continue;
+        }
+        if run_iteration(computer, shape, a)? != wanted {
+            continue;
+        }
+        if iteration > 0 {
+            if let Some(a) =
+                search_register_a(computer, shape, expected, iterations, iteration - 1, a)?
+            {
+                return Ok(Some(a));
+            }
+            continue;
+        }
+
+        // Run the whole program to be sure
+        computer.reset();
+        computer.register_a = a;
+        computer.execute()?;
+        if computer.output == expected {
+            return Ok(Some(a));
+        }
+    }
+    Ok(None)
+}
+
+/// Runs a single iteration of the loop, starting with `a` in A, and
+/// returns what it wrote.
+fn run_iteration<'a>(
+    computer: &'a mut Program,
+    shape: &Loop,
+    a: i64,
+) -> Result<&'a [u8], SolveError> {
+    computer.reset();
+    computer.register_a = a;
+    computer.instruction_pointer = shape.start;
+    while let Some(step) = computer.step()? {
+        if step.instruction.position == shape.end {
+            break;
+        }
+    }
+    Ok(&computer.output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_instructions() {
+        // If register C contains 9, the program 2,6 would set register B to 1.
+        let mut program = Program::new(0, 0, 9, vec![2, 6]);
+        program.execute().unwrap();
+        assert_eq!(program.register_b, 1);
+
+        // If register A contains 10, the program 5,0,5,1,5,4 would output 0,1,2.
+        let mut program = Program::new(10, 0, 0, vec![5, 0, 5, 1, 5, 4]);
+        program.execute().unwrap();
+        assert_eq!(program.output, vec![0, 1, 2]);
+
+        // If register A contains 2024, the program 0,1,5,4,3,0 would output 4,2,5,6,7,7,7,7,3,1,0 and leave 0 in register A.
+        let mut program = Program::new(2024, 0, 0, vec![0, 1, 5, 4, 3, 0]);
+        program.execute().unwrap();
+        assert_eq!(program.output, vec![4, 2, 5, 6, 7, 7, 7, 7, 3, 1, 0]);
+        assert_eq!(program.register_a, 0);
+
+        // If register B contains 29, the program 1,7 would set register B to 26.
+        let mut program = Program::new(0, 29, 0, vec![1, 7]);
+        program.execute().unwrap();
+        assert_eq!(program.register_b, 26);
+
+        // If register B contains 2024 and register C contains 43690, the program 4,0 would set register B to 44354.
+        let mut program = Program::new(0, 2024, 43690, vec![4, 0]);
+        program.execute().unwrap();
+        assert_eq!(program.register_b, 44354);
+
+        // A shift by a huge register empties A instead of overflowing
+        let mut program = Program::new(1 << 40, 70, 0, vec![0, 5]);
+        program.execute().unwrap();
+        assert_eq!(program.register_a, 0);
+    }
+
+    #[test]
+    fn test_disassemble() {
+        assert_eq!(
+            disassembly(&[2, 4, 1, 5, 7, 5, 0, 3, 4, 1, 5, 5, 3, 0]).unwrap(),
+            "  0: bst a   ; b = a % 8
+  2: bxl 5   ; b = b ^ 5
+  4: cdv b   ; c = a >> b
+  6: adv 3   ; a = a >> 3
+  8: bxc 1   ; b = b ^ c
+ 10: out b   ; output b % 8
+ 12: jnz 0   ; if a != 0 goto 0
+"
+        );
+        assert_eq!(
+            disassemble(&[0, 3, 9]),
+            Ok(vec![Instruction {
+                position: 0,
+                opcode: Opcode::Adv,
+                operand: 3
+            }])
+        );
+        assert_eq!(
+            disassemble(&[0, 1, 9, 0]),
+            Err(SolveError::InvalidOpcode {
+                opcode: 9,
+                position: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_trace() {
+        let mut program = Program::new(10, 0, 0, vec![0, 1, 5, 4, 3, 0]);
+        let steps = program.trace().collect::<Result<Vec<_>, _>>().unwrap();
+        // 4 iterations of 3 instructions
+        assert_eq!(steps.len(), 12);
+        assert_eq!(steps[0].registers, Registers { a: 5, b: 0, c: 0 });
+        assert_eq!(steps[1].output, Some(5));
+        assert_eq!(steps[2].instruction.opcode, Opcode::Jnz);
+        assert_eq!(
+            steps[1].to_string(),
+            "  2: out a   ; output a % 8      a=5 b=0 c=0 out=5"
+        );
+        assert_eq!(program.instructions, 12);
+        assert_eq!(program.step(), Ok(None));
+
+        let mut program = Program::new(1, 0, 0, vec![3, 0]).with_budget(5);
+        let steps = program.trace().collect::<Vec<_>>();
+        assert_eq!(steps.len(), 6);
+        assert_eq!(steps[5], Err(SolveError::InfiniteLoop { instructions: 5 }));
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut program = Program::new(0, 0, 0, vec![0, 1, 8, 0]);
+        assert_eq!(
+            program.execute(),
+            Err(SolveError::InvalidOpcode {
+                opcode: 8,
+                position: 2
+            })
+        );
+        let mut program = Program::new(0, 0, 0, vec![5, 7]);
+        assert_eq!(
+            program.execute(),
+            Err(SolveError::InvalidOperand {
+                operand: 7,
+                position: 1
+            })
+        );
+        let mut program = Program::new(1, 0, 0, vec![3, 0]);
+        assert_eq!(program.budget, DEFAULT_BUDGET);
+        program.budget = 1000;
+        assert_eq!(
+            program.execute(),
+            Err(SolveError::InfiniteLoop { instructions: 1000 })
+        );
+    }
+
+    #[test]
+    fn test_analyze_loop() {
+        assert_eq!(
+            analyze_loop(&[2, 4, 1, 5, 7, 5, 0, 3, 4, 1, 5, 5, 3, 0]),
+            Ok(Loop {
+                start: 0,
+                end: 12,
+                shift: 3,
+                outputs: 1
+            })
+        );
+        // B set once before the loop, two values per iteration
+        assert_eq!(
+            analyze_loop(&[2, 3, 5, 4, 6, 1, 5, 5, 0, 3, 3, 2]),
+            Ok(Loop {
+                start: 2,
+                end: 10,
+                shift: 3,
+                outputs: 2
+            })
+        );
+
+        let unsupported = |program: &[u8]| match analyze_loop(program) {
+            Err(SolveError::Unsupported(reason)) => reason,
+            result => panic!("{:?} for {:?}", result, program),
+        };
+        assert!(unsupported(&[0, 3, 5, 4]).contains("no jnz"));
+        assert!(unsupported(&[0, 3, 3, 0, 5, 4, 3, 0]).contains("2 jnz"));
+        assert!(unsupported(&[0, 3, 3, 1]).contains("backwards"));
+        assert!(unsupported(&[2, 4, 0, 5, 5, 4, 3, 0]).contains("constant"));
+        assert!(unsupported(&[0, 1, 0, 1, 5, 4, 3, 0]).contains("more than once"));
+        assert!(unsupported(&[1, 2, 0, 3, 5, 5, 3, 0]).contains("B is read"));
+        assert!(unsupported(&[5, 4, 0, 3, 5, 4, 3, 2]).contains("outside of the loop"));
+        assert!(unsupported(&[0, 3, 3, 0, 5, 4]).contains("outside of the loop"));
+        assert!(unsupported(&[0, 1, 2, 4, 0, 3, 5, 5, 3, 2]).contains("A is written at position 0"));
+        assert!(unsupported(&[5, 4, 3, 0]).contains("never ends"));
+        assert!(unsupported(&[0, 3, 3, 0]).contains("doesn't output"));
+    }
+
+    #[test]
+    fn test_lowest_register_a() {
+        let mut program = Program::new(12345, 0, 0, vec![2, 3, 5, 4, 6, 1, 5, 5, 0, 3, 3, 2]);
+        program.execute().unwrap();
+        let expected = program.output.clone();
+
+        let lowest = (0..=12345)
+            .find(|&a| {
+                program.reset();
+                program.register_a = a;
+                program.execute().unwrap();
+                program.output == expected
+            })
+            .unwrap();
+        assert_eq!(lowest_register_a(&program, &expected), Ok(lowest));
+
+        // An odd number of values can't come from two per iteration
+        assert!(matches!(
+            lowest_register_a(&program, &expected[1..]),
+            Err(SolveError::Unsupported(_))
+        ));
+        // The second value is the first one shifted, 7 can't follow 0
+        assert_eq!(
+            lowest_register_a(&program, &[0, 7]),
+            Err(SolveError::NoSolution)
+        );
+    }
+}
@@ -0,0 +1,772 @@
+/*
+    The commands of the binary, without their command line. `main.rs`
+    only declares the arguments with clap and hands them over here, so
+    another front end can select the days, run them, benchmark them or
+    use the tools of days 14, 24 and 25 the same way.
+
+    A command prints its results and its summary itself. When it stops,
+    it returns a `CommandError` and the caller decides how to exit.
+*/
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+    ops::RangeInclusive,
+    path::PathBuf,
+    str::FromStr,
+};
+
+use crate::{
+    answers::{Answers, Verdict},
+    bench::{bench_day, change_percent, Baseline},
+    day_14::{self, FrameFormat},
+    day_24, day_25,
+    grid::Size,
+    inputs::{InputOverride, InputSource},
+    output::{Format, Printer},
+    params::{ParamOverride, Params},
+    parsing::ParseError,
+    runner::execute_day,
+    solution::{self, DynSolution},
+};
+
+#[derive(Debug)]
+pub enum CommandError {
+    /// The arguments don't go together, like an input for a day that
+    /// isn't selected.
+    Usage(String),
+    /// The input of a single day command doesn't parse.
+    Parse(ParseError),
+    /// A day can't do what the command asks.
+    Day(u8, String),
+    /// Anything else that stops the command, like a file it can't write.
+    Other(String),
+    /// The command went to the end but some of it failed, its summary
+    /// tells what.
+    Failed,
+}
+
+impl Display for CommandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            CommandError::Usage(message) | CommandError::Other(message) => {
+                write!(f, "{}", message)
+            }
+            CommandError::Parse(error) => writeln!(f, "{}", error.report()),
+            CommandError::Day(day, message) => write!(f, "Day {}: {}", day_name(*day), message),
+            CommandError::Failed => write!(f, "the command failed"),
+        }
+    }
+}
+
+/// A `DAYS` argument, either a single day (`5`) or an inclusive range
+/// (`5-12`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct DaySelection(pub RangeInclusive<u8>);
+
+impl FromStr for DaySelection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_day = |day: &str| {
+            day.trim()
+                .parse::<u8>()
+                .map_err(|_| format!("`{}` is not a day number", day))
+        };
+        let range = match s.split_once('-') {
+            Some((start, end)) => parse_day(start)?..=parse_day(end)?,
+            None => {
+                let day = parse_day(s)?;
+                day..=day
+            }
+        };
+        if range.is_empty() {
+            return Err(format!("`{}` is an empty range", s));
+        }
+        Ok(DaySelection(range))
+    }
+}
+
+fn day_name(day: u8) -> String {
+    format!("{:02}", day)
+}
+
+/// The days and parts to run, and where their inputs are, as given on
+/// the command line.
+#[derive(Debug, Clone, Default)]
+pub struct Selection {
+    /// Every registered day when empty.
+    pub days: Vec<DaySelection>,
+    pub part: Option<u8>,
+    pub inputs_dir: PathBuf,
+    pub inputs: Vec<InputOverride>,
+    pub params: Vec<ParamOverride>,
+}
+
+/// A selection checked against the registry.
+#[derive(Debug)]
+pub struct Selected {
+    pub days: BTreeSet<u8>,
+    inputs_dir: PathBuf,
+    part: Option<u8>,
+    overrides: Vec<(u8, InputSource)>,
+    params: BTreeMap<u8, Params>,
+    no_params: Params,
+}
+
+impl Selection {
+    /// The selected days, with their inputs and their parameters. The
+    /// days must be registered, and the inputs and the parameters must
+    /// go to selected days that know them.
+    pub fn resolve(&self, days: &[&dyn DynSolution]) -> Result<Selected, CommandError> {
+        let selected: BTreeSet<u8> = if self.days.is_empty() {
+            days.iter().map(|day| day.day()).collect()
+        } else {
+            let mut selected = BTreeSet::new();
+            for DaySelection(range) in &self.days {
+                for number in range.clone() {
+                    if solution::find(number).is_none() {
+                        return Err(CommandError::Usage(format!(
+                            "day {} is not registered",
+                            number
+                        )));
+                    }
+                    selected.insert(number);
+                }
+            }
+            selected
+        };
+
+        let mut overrides: Vec<(u8, InputSource)> = Vec::new();
+        for InputOverride { day, source } in &self.inputs {
+            let day = match *day {
+                Some(day) if selected.contains(&day) => day,
+                Some(day) => {
+                    return Err(CommandError::Usage(format!(
+                        "day {} has an input but is not selected",
+                        day
+                    )))
+                }
+                None if selected.len() == 1 => *selected.first().unwrap(),
+                None => {
+                    return Err(CommandError::Usage(
+                        "an input without a day requires a single selected day, use DAY=PATH"
+                            .to_string(),
+                    ))
+                }
+            };
+            if overrides.iter().any(|(other, _)| *other == day) {
+                return Err(CommandError::Usage(format!(
+                    "day {} has more than one input",
+                    day
+                )));
+            }
+            overrides.push((day, source.clone()));
+        }
+        if overrides
+            .iter()
+            .filter(|(_, source)| *source == InputSource::Stdin)
+            .count()
+            > 1
+        {
+            return Err(CommandError::Usage(
+                "only one day can read its input from the standard input".to_string(),
+            ));
+        }
+
+        let mut params: BTreeMap<u8, Params> = BTreeMap::new();
+        for ParamOverride { day, name, value } in &self.params {
+            let day = match *day {
+                Some(day) if selected.contains(&day) => day,
+                Some(day) => {
+                    return Err(CommandError::Usage(format!(
+                        "day {} has a parameter but is not selected",
+                        day
+                    )))
+                }
+                None if selected.len() == 1 => *selected.first().unwrap(),
+                None => {
+                    return Err(CommandError::Usage(
+                        "a parameter without a day requires a single selected day, \
+                         use DAY.NAME=VALUE"
+                            .to_string(),
+                    ))
+                }
+            };
+            let known = solution::find(day)
+                .expect("The selected days are registered")
+                .params();
+            if !known.iter().any(|param| param.name == name) {
+                return Err(CommandError::Usage(format!(
+                    "day {} has no parameter `{}`",
+                    day, name
+                )));
+            }
+            params.entry(day).or_default().set(name, value);
+        }
+
+        Ok(Selected {
+            days: selected,
+            inputs_dir: self.inputs_dir.clone(),
+            part: self.part,
+            overrides,
+            params,
+            no_params: Params::default(),
+        })
+    }
+}
+
+impl Selected {
+    /// The selected parts, both of them unless one is given.
+    pub fn parts(&self) -> Vec<u8> {
+        [1, 2]
+            .into_iter()
+            .filter(|number| self.part.is_none_or(|part| part == *number))
+            .collect()
+    }
+
+    pub fn input_source(&self, day: u8) -> InputSource {
+        self.overrides
+            .iter()
+            .find(|(number, _)| *number == day)
+            .map(|(_, source)| source.clone())
+            .unwrap_or_else(|| InputSource::for_day(&self.inputs_dir, day))
+    }
+
+    pub fn params(&self, day: u8) -> &Params {
+        self.params.get(&day).unwrap_or(&self.no_params)
+    }
+}
+
+/// The input of a day, or `None` once the error is reported.
+fn load_input(day: u8, source: &InputSource) -> Option<String> {
+    source
+        .load()
+        .map_err(|error| eprintln!("Day {}: {}", day_name(day), error))
+        .ok()
+}
+
+fn report_parse_error(error: &ParseError) {
+    eprintln!("{}\n", error.report());
+}
+
+fn report_failed_inputs(failed_inputs: &[String]) {
+    if !failed_inputs.is_empty() {
+        eprintln!(
+            "Could not load the input of {} day(s): {}",
+            failed_inputs.len(),
+            failed_inputs.join(", ")
+        );
+    }
+}
+
+pub struct RunOptions {
+    pub selection: Selection,
+    pub format: Format,
+    /// File of the expected answers the results are verified against.
+    pub answers: PathBuf,
+    /// Write the answers of this run to the answers file instead of
+    /// failing on the ones that differ.
+    pub record: bool,
+    /// Run the puzzle examples instead of the inputs.
+    pub examples: bool,
+}
+
+/// Runs the selected days and verifies their answers, against the
+/// answers file or the examples.
+pub fn run(options: &RunOptions, days: &[&dyn DynSolution]) -> Result<(), CommandError> {
+    let selected = options.selection.resolve(days)?;
+
+    let mut answers =
+        Answers::load(&options.answers).map_err(|error| CommandError::Other(error.to_string()))?;
+
+    let mut printer = Printer::new(options.format);
+    let mut failed_inputs = Vec::new();
+    let mut failed_parts = Vec::new();
+    let mut parse_errors = Vec::new();
+    let mut wrong_answers = Vec::new();
+    let mut recorded = 0;
+    for &day in days.iter().filter(|day| selected.days.contains(&day.day())) {
+        let parts = selected.parts();
+        // An example only runs the parts it has an answer for, the
+        // other part may not even make sense on it.
+        let inputs = if options.examples {
+            day.examples()
+                .iter()
+                .map(|example| {
+                    let parts = parts
+                        .iter()
+                        .copied()
+                        .filter(|&part| example.answer(part).is_some())
+                        .collect();
+                    (example.data.to_string(), parts, Some(example))
+                })
+                .collect()
+        } else {
+            match load_input(day.day(), &selected.input_source(day.day())) {
+                Some(data) => vec![(data, parts, None)],
+                None => {
+                    failed_inputs.push(day_name(day.day()));
+                    continue;
+                }
+            }
+        };
+        for (data, parts, example) in inputs {
+            let day_params = selected.params(day.day());
+            let results = match example {
+                Some(example) => execute_day(day, &data, &parts, &example.params(day_params)),
+                None => execute_day(day, &data, &parts, day_params),
+            };
+            parse_errors.extend(results.parse_error);
+            for mut result in results.parts {
+                let name = format!("{}.{}", day_name(day.day()), result.part);
+                match &result.outcome {
+                    Ok(answer) => {
+                        result.verdict = match example {
+                            Some(example) => example.verify(result.part, answer),
+                            None => answers.verify(day.day(), result.part, answer),
+                        };
+                        if let Verdict::Fail(_) = result.verdict {
+                            wrong_answers.push(name);
+                        }
+                        if options.record {
+                            answers.record(day.day(), result.part, answer.clone());
+                            recorded += 1;
+                        }
+                    }
+                    Err(error) => failed_parts.push(format!("{}: {}", name, error)),
+                }
+                printer.print(&result);
+            }
+        }
+    }
+
+    printer.finish();
+
+    for error in &parse_errors {
+        report_parse_error(error);
+    }
+
+    if options.record {
+        answers
+            .save(&options.answers)
+            .map_err(|error| CommandError::Other(error.to_string()))?;
+        eprintln!(
+            "Recorded {} answer(s) in {}",
+            recorded,
+            options.answers.display()
+        );
+    }
+
+    report_failed_inputs(&failed_inputs);
+    if !failed_parts.is_empty() {
+        eprintln!("{} part(s) failed:", failed_parts.len());
+        for failure in &failed_parts {
+            eprintln!("  {}", failure);
+        }
+    }
+    if !wrong_answers.is_empty() {
+        eprintln!(
+            "{} answer(s) differ from {}{}: {}",
+            wrong_answers.len(),
+            if options.examples {
+                "the examples".to_string()
+            } else {
+                options.answers.display().to_string()
+            },
+            if options.record { ", now recorded" } else { "" },
+            wrong_answers.join(", ")
+        );
+    }
+    if !failed_inputs.is_empty()
+        || !failed_parts.is_empty()
+        || (!wrong_answers.is_empty() && !options.record)
+    {
+        return Err(CommandError::Failed);
+    }
+    Ok(())
+}
+
+pub struct BenchOptions {
+    pub selection: Selection,
+    /// Number of measured runs of each day.
+    pub runs: u32,
+    /// Number of runs before the measured ones.
+    pub warmup: u32,
+    /// File of the baseline medians the results are compared to.
+    pub baseline: PathBuf,
+    /// Save the medians of this run in the baseline file.
+    pub save_baseline: bool,
+    /// Slowdown of the median, in percent, above which a step is a
+    /// regression.
+    pub threshold: f64,
+}
+
+/// Measures the selected days and compares them to the baseline.
+pub fn bench(options: &BenchOptions, days: &[&dyn DynSolution]) -> Result<(), CommandError> {
+    let selected = options.selection.resolve(days)?;
+
+    let mut baseline = Baseline::load(&options.baseline)
+        .map_err(|error| CommandError::Other(error.to_string()))?;
+
+    println!(
+        "{:<4} {:<7} {:>12} {:>12} {:>12} {:>12} {:>12} {:>9}",
+        "Day", "Step", "Min", "Median", "Mean", "Stddev", "Baseline", "Change"
+    );
+    let mut failed_inputs = Vec::new();
+    let mut failed_steps = Vec::new();
+    let mut regressions = Vec::new();
+    let parts = selected.parts();
+    for &day in days.iter().filter(|day| selected.days.contains(&day.day())) {
+        let Some(data) = load_input(day.day(), &selected.input_source(day.day())) else {
+            failed_inputs.push(day_name(day.day()));
+            continue;
+        };
+        for (step, stats) in bench_day(
+            day,
+            &data,
+            &parts,
+            options.warmup,
+            options.runs,
+            selected.params(day.day()),
+        ) {
+            let name = format!("{} {}", day_name(day.day()), step);
+            let stats = match stats {
+                Ok(stats) => stats,
+                Err(error) => {
+                    println!(
+                        "{:<4} {:<7} failed: {}",
+                        day_name(day.day()),
+                        step.to_string(),
+                        error
+                    );
+                    failed_steps.push(name);
+                    continue;
+                }
+            };
+            let previous = baseline.get(day.day(), step);
+            let change = previous.map(|previous| change_percent(previous, stats.median));
+            if change.is_some_and(|change| change > options.threshold) {
+                regressions.push(name);
+            }
+            println!(
+                "{:<4} {:<7} {:>12} {:>12} {:>12} {:>12} {:>12} {:>9}",
+                day_name(day.day()),
+                step.to_string(),
+                format!("{:.2?}", stats.min),
+                format!("{:.2?}", stats.median),
+                format!("{:.2?}", stats.mean),
+                format!("{:.2?}", stats.stddev),
+                previous.map_or("-".to_string(), |previous| format!("{:.2?}", previous)),
+                change.map_or("-".to_string(), |change| format!("{:+.1}%", change)),
+            );
+            if options.save_baseline {
+                baseline.set(day.day(), step, stats.median);
+            }
+        }
+    }
+
+    if options.save_baseline {
+        baseline
+            .save(&options.baseline)
+            .map_err(|error| CommandError::Other(error.to_string()))?;
+        eprintln!("Saved the baseline in {}", options.baseline.display());
+    }
+
+    report_failed_inputs(&failed_inputs);
+    if !failed_steps.is_empty() {
+        eprintln!(
+            "{} step(s) failed: {}",
+            failed_steps.len(),
+            failed_steps.join(", ")
+        );
+    }
+    if !regressions.is_empty() {
+        eprintln!(
+            "{} step(s) are more than {}% slower than {}: {}",
+            regressions.len(),
+            options.threshold,
+            options.baseline.display(),
+            regressions.join(", ")
+        );
+    }
+    if !failed_inputs.is_empty() || !failed_steps.is_empty() || !regressions.is_empty() {
+        return Err(CommandError::Failed);
+    }
+    Ok(())
+}
+
+/// Prints the registered days, with their examples and parameters.
+pub fn list(days: &[&dyn DynSolution]) {
+    println!("{:<4} {:<24} {:<8} Parameters", "Day", "Title", "Examples");
+    for day in days {
+        println!(
+            "{:<4} {:<24} {:<8} {}",
+            day_name(day.day()),
+            day.name(),
+            day.examples().len(),
+            day.params()
+                .iter()
+                .map(|param| format!("{} ({})", param.name, param.help))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+}
+
+/// The input of the commands working on a single day.
+#[derive(Debug, Clone, Default)]
+pub struct DayInput {
+    /// Input file to use instead of the one in the inputs directory.
+    pub input: Option<PathBuf>,
+    pub inputs_dir: PathBuf,
+}
+
+impl DayInput {
+    /// The parsed input of `day`.
+    pub fn parse<T>(
+        &self,
+        day: u8,
+        parse: impl FnOnce(&str) -> Result<T, ParseError>,
+    ) -> Result<T, CommandError> {
+        let source = match &self.input {
+            Some(path) => InputSource::File(path.clone()),
+            None => InputSource::for_day(&self.inputs_dir, day),
+        };
+        let data = source
+            .load()
+            .map_err(|error| CommandError::Day(day, error.to_string()))?;
+        parse(&data).map_err(CommandError::Parse)
+    }
+}
+
+pub struct RobotsOptions {
+    pub input: DayInput,
+    pub size: Size,
+    /// The seconds to export, unless they are around the tree.
+    pub times: RangeInclusive<i64>,
+    /// Export this many seconds before and after the tree of part 2
+    /// instead.
+    pub around_tree: Option<i64>,
+    pub format: FrameFormat,
+    /// Directory the frames are written to, or `-` to print them.
+    pub output: PathBuf,
+}
+
+/// Exports the frames of the day 14 robots.
+pub fn robots(options: &RobotsOptions) -> Result<(), CommandError> {
+    let robots = options.input.parse(14, day_14::day_14_parse)?;
+
+    let times = match options.around_tree {
+        Some(radius) => {
+            let room = day_14::Room {
+                size: options.size,
+                ..Default::default()
+            };
+            let tree = day_14::tree_time_by_variance(&robots, room)
+                .map_err(|error| CommandError::Day(14, error.to_string()))?;
+            eprintln!("The tree is at {} seconds", tree);
+            let last = tree.checked_add(radius).ok_or_else(|| {
+                CommandError::Usage(format!(
+                    "{} seconds after the tree at {} seconds is too far",
+                    radius, tree
+                ))
+            })?;
+            (tree - radius).max(0)..=last
+        }
+        None => options.times.clone(),
+    };
+    if times.is_empty() {
+        return Err(CommandError::Usage(format!(
+            "no second between {} and {}",
+            times.start(),
+            times.end()
+        )));
+    }
+
+    if options.output.as_os_str() == "-" {
+        for time in times {
+            println!("After {} seconds:", time);
+            println!(
+                "{}",
+                day_14::render_frame(&robots, options.size, time, options.format)
+            );
+        }
+        return Ok(());
+    }
+    let paths = day_14::export_frames(
+        &robots,
+        options.size,
+        times,
+        options.format,
+        &options.output,
+    )
+    .map_err(|error| CommandError::Other(format!("Could not write the frames: {}", error)))?;
+    eprintln!(
+        "Wrote {} frame(s) in {}",
+        paths.len(),
+        options.output.display()
+    );
+    Ok(())
+}
+
+pub struct CircuitOptions {
+    pub input: DayInput,
+    /// Wires to draw in red.
+    pub highlight: Vec<String>,
+    /// Draw the wires found by part 2 in red too, and print why.
+    pub swapped: bool,
+    /// File the graph is written to, or `-` to print it.
+    pub output: PathBuf,
+}
+
+/// Exports the day 24 circuit as a Graphviz DOT graph.
+pub fn circuit(options: &CircuitOptions) -> Result<(), CommandError> {
+    let problem = options.input.parse(24, day_24::day_24_parse)?;
+    let day_error = |error: solution::SolveError| CommandError::Day(24, error.to_string());
+
+    let mut highlighted = options.highlight.clone();
+    if options.swapped {
+        let violations = day_24::adder_violations(&problem).map_err(day_error)?;
+        eprint!("{}", day_24::adder_report(&problem).map_err(day_error)?);
+        highlighted.extend(violations.into_iter().map(|violation| violation.wire));
+    }
+    let dot = day_24::circuit_dot(&problem, &highlighted).map_err(day_error)?;
+
+    if options.output.as_os_str() == "-" {
+        print!("{}", dot);
+    } else if let Err(error) = std::fs::write(&options.output, dot) {
+        return Err(CommandError::Other(format!(
+            "Could not write {}: {}",
+            options.output.display(),
+            error
+        )));
+    }
+    Ok(())
+}
+
+pub struct AdderOptions {
+    pub input: DayInput,
+    /// Numbers set on the x and y wires, the ones of the input when not
+    /// given.
+    pub numbers: Option<(u64, u64)>,
+    /// Add this many pairs of random numbers instead, with their seed.
+    pub random: Option<(usize, u64)>,
+}
+
+/// Adds numbers with the day 24 circuit and checks the sums.
+pub fn adder(options: &AdderOptions) -> Result<(), CommandError> {
+    let problem = options.input.parse(24, day_24::day_24_parse)?;
+    let circuit =
+        day_24::Circuit::new(&problem).map_err(|error| CommandError::Day(24, error.to_string()))?;
+
+    let additions = match options.random {
+        Some((count, seed)) => circuit.random_additions(count, seed),
+        None => {
+            let (x, y) = options.numbers.unwrap_or_else(|| circuit.inputs());
+            vec![circuit
+                .add(x, y)
+                .map_err(|error| CommandError::Usage(error.to_string()))?]
+        }
+    };
+
+    let wrong = additions
+        .iter()
+        .filter(|addition| addition.lowest_failing_bit().is_some())
+        .collect::<Vec<_>>();
+    for addition in &additions {
+        if options.random.is_none() || addition.lowest_failing_bit().is_some() {
+            println!("{}", addition);
+        }
+    }
+    if options.random.is_some() {
+        eprintln!(
+            "{} of {} sum(s) are right",
+            additions.len() - wrong.len(),
+            additions.len()
+        );
+    }
+    if let Some(bit) = wrong
+        .iter()
+        .filter_map(|addition| addition.lowest_failing_bit())
+        .min()
+    {
+        eprintln!("The lowest wrong bit is {}", bit);
+        return Err(CommandError::Failed);
+    }
+    Ok(())
+}
+
+/// Prints which key of day 25 fits which lock.
+pub fn locks(input: &DayInput) -> Result<(), CommandError> {
+    let problem = input.parse(25, day_25::day_25_parse)?;
+    print!("{}", problem.fitting_report());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::SOLUTIONS;
+
+    #[test]
+    fn test_parse_day_selection() {
+        assert_eq!("5".parse(), Ok(DaySelection(5..=5)));
+        assert_eq!("5-12".parse(), Ok(DaySelection(5..=12)));
+        assert!("12-5".parse::<DaySelection>().is_err());
+        assert!("five".parse::<DaySelection>().is_err());
+    }
+
+    #[test]
+    fn test_resolve_selection() {
+        let selection = Selection {
+            days: vec![DaySelection(14..=14)],
+            part: Some(2),
+            inputs_dir: PathBuf::from("inputs"),
+            inputs: vec!["-".parse().unwrap()],
+            params: vec!["size=11x7".parse().unwrap()],
+        };
+        let selected = selection.resolve(SOLUTIONS).unwrap();
+        assert_eq!(selected.days, BTreeSet::from([14]));
+        assert_eq!(selected.parts(), vec![2]);
+        assert_eq!(selected.input_source(14), InputSource::Stdin);
+        assert_eq!(
+            selected.params(14).get::<Size>("size"),
+            Ok(Some(Size {
+                width: 11,
+                height: 7
+            }))
+        );
+        assert_eq!(
+            selected.input_source(15),
+            InputSource::File(PathBuf::from("inputs/day_15.txt"))
+        );
+
+        let usage = |selection: Selection| match selection.resolve(SOLUTIONS) {
+            Err(CommandError::Usage(message)) => message,
+            other => panic!("{:?}", other),
+        };
+        assert_eq!(
+            usage(Selection {
+                days: vec![DaySelection(1..=2)],
+                inputs: vec!["-".parse().unwrap()],
+                ..selection.clone()
+            }),
+            "an input without a day requires a single selected day, use DAY=PATH"
+        );
+        assert_eq!(
+            usage(Selection {
+                params: vec!["14.render=true".parse().unwrap()],
+                ..selection.clone()
+            }),
+            "day 14 has no parameter `render`"
+        );
+        assert_eq!(
+            usage(Selection {
+                days: vec![DaySelection(26..=26)],
+                ..selection
+            }),
+            "day 26 is not registered"
+        );
+    }
+}
@@ -5,6 +5,12 @@ use nom::{
     IResult,
 };
 
+use crate::{
+    answer::Answer,
+    parsing::{parse_all, ParseError},
+    solution::{Example, Solution, SolveError},
+};
+
 fn parse_input_data(data: &str) -> IResult<&str, Vec<(i64, i64)>> {
     separated_list0(
         line_ending,
@@ -16,9 +22,11 @@ fn parse_input_data(data: &str) -> IResult<&str, Vec<(i64, i64)>> {
     )(data)
 }
 
-pub fn day_01_part_1(data: &str) -> i64 {
-    let (_, data) = parse_input_data(data).expect("Failed to parse input data");
+pub fn day_01_parse(data: &str) -> Result<Vec<(i64, i64)>, ParseError> {
+    parse_all(1, data, parse_input_data)
+}
 
+pub fn day_01_part_1(data: &[(i64, i64)]) -> i64 {
     let mut left_list = data.iter().map(|(a, _)| *a).collect::<Vec<i64>>();
     let mut right_list = data.iter().map(|(_, b)| *b).collect::<Vec<i64>>();
 
@@ -34,50 +42,101 @@ pub fn day_01_part_1(data: &str) -> i64 {
     difference
 }
 
-pub fn day_01_part_2(data: &str) -> i64 {
-    let (_, data) = parse_input_data(data).expect("Failed to parse input data");
-
+pub fn day_01_part_2(data: &[(i64, i64)]) -> Result<i64, SolveError> {
     // Create a 100 000 sized array, that's about 781KB
     // We could have used a Map/Dict too, but I like the array tonight.
     let mut nb_of_appearances = vec![0_usize; 100_000];
 
     // Fill the array with the number of appearances
     for (_, location) in data.iter() {
-        #[allow(clippy::manual_range_contains)]
-        if *location < 0 || *location > 100_000 {
-            panic!("Invalid location: {}", location);
-        }
-        nb_of_appearances[*location as usize] += 1;
+        let count = usize::try_from(*location)
+            .ok()
+            .and_then(|index| nb_of_appearances.get_mut(index))
+            .ok_or_else(|| {
+                SolveError::Unsupported(format!("location {} is outside of 0..100000", location))
+            })?;
+        *count += 1;
     }
 
+    // Locations outside of the array never appear in the right list
     let sum = data.iter().fold(0, |acc, (location, _)| {
-        if *location < 0 || *location > 100_000 {
-            panic!("Invalid location: {}", location);
-        }
-        acc + *location * nb_of_appearances[*location as usize] as i64
+        let appearances = usize::try_from(*location)
+            .ok()
+            .and_then(|index| nb_of_appearances.get(index))
+            .copied()
+            .unwrap_or(0);
+        acc + *location * appearances as i64
     });
 
-    sum
+    Ok(sum)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const EXAMPLE: &str = "3   4
+const EXAMPLE: &str = "3   4
 4   3
 2   5
 1   3
 3   9
 3   3";
 
+pub struct Day01;
+
+impl Solution for Day01 {
+    type Input = Vec<(i64, i64)>;
+
+    fn day(&self) -> u8 {
+        1
+    }
+
+    fn name(&self) -> &'static str {
+        "Historian Hysteria"
+    }
+
+    fn parse(&self, data: &str) -> Result<Self::Input, ParseError> {
+        day_01_parse(data)
+    }
+
+    fn part_1(&self, input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(day_01_part_1(input).into())
+    }
+
+    fn part_2(&self, input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(day_01_part_2(input)?.into())
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            data: EXAMPLE,
+            part_1: Some("11"),
+            part_2: Some("31"),
+            params: &[],
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     #[test]
     fn test_day_01_part_1() {
-        assert_eq!(day_01_part_1(EXAMPLE), 11);
+        assert_eq!(day_01_part_1(&day_01_parse(EXAMPLE).unwrap()), 11);
     }
 
     #[test]
     fn test_day_01_part_2() {
-        assert_eq!(day_01_part_2(EXAMPLE), 31);
+        assert_eq!(day_01_part_2(&day_01_parse(EXAMPLE).unwrap()), Ok(31));
+    }
+
+    #[test]
+    fn test_day_01_part_2_out_of_range() {
+        assert_eq!(day_01_part_2(&[(100_000, 3)]), Ok(0));
+        assert!(matches!(
+            day_01_part_2(&[(3, 100_000)]),
+            Err(SolveError::Unsupported(_))
+        ));
+        assert!(matches!(
+            day_01_part_2(&[(3, -1)]),
+            Err(SolveError::Unsupported(_))
+        ));
     }
 }
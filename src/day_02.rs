@@ -4,20 +4,29 @@
 
 use nom::{
     character::complete::{line_ending, space1},
-    multi::separated_list0,
+    combinator::consumed,
+    multi::{separated_list0, separated_list1},
     IResult,
 };
 
-fn parse_input_data(data: &str) -> IResult<&str, Vec<Vec<i64>>> {
+use crate::{
+    answer::Answer,
+    parsing::{parse_all, ParseError},
+    solution::{Example, Solution, SolveError},
+};
+
+// Each report comes with its line, to point at it when it's too short.
+fn parse_input_data(data: &str) -> IResult<&str, Vec<(&str, Vec<i64>)>> {
     separated_list0(
         line_ending,
-        separated_list0(space1, nom::character::complete::i64),
+        consumed(separated_list1(space1, nom::character::complete::i64)),
     )(data)
 }
 
 fn is_report_safe(report: &[i64]) -> bool {
+    // A single level is always safe, it can happen once a level is removed
     if report.len() < 2 {
-        panic!("Invalid report length: {}", report.len());
+        return true;
     }
     use std::cmp::Ordering;
 
@@ -34,17 +43,31 @@ fn is_report_safe(report: &[i64]) -> bool {
     })
 }
 
-pub fn day_02_part_1(data: &str) -> i64 {
-    let (_, data) = parse_input_data(data).expect("Failed to parse input data");
+pub fn day_02_parse(data: &str) -> Result<Vec<Vec<i64>>, ParseError> {
+    let reports = parse_all(2, data, parse_input_data)?;
+    reports
+        .into_iter()
+        .map(|(line, report)| {
+            if report.len() < 2 {
+                return Err(ParseError::malformed(
+                    2,
+                    data,
+                    line,
+                    "a report needs at least 2 levels",
+                ));
+            }
+            Ok(report)
+        })
+        .collect()
+}
 
+pub fn day_02_part_1(data: &[Vec<i64>]) -> i64 {
     data.iter()
         .map(|report| is_report_safe(report) as i64)
         .sum()
 }
 
-pub fn day_02_part_2(data: &str) -> i64 {
-    let (_, data) = parse_input_data(data).expect("Failed to parse input data");
-
+pub fn day_02_part_2(data: &[Vec<i64>]) -> i64 {
     data.iter()
         .map(|report| {
             // quick exit
@@ -66,24 +89,69 @@ pub fn day_02_part_2(data: &str) -> i64 {
         .sum()
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const EXAMPLE: &str = "7 6 4 2 1
+const EXAMPLE: &str = "7 6 4 2 1
 1 2 7 8 9
 9 7 6 2 1
 1 3 2 4 5
 8 6 4 4 1
 1 3 6 7 9";
 
+pub struct Day02;
+
+impl Solution for Day02 {
+    type Input = Vec<Vec<i64>>;
+
+    fn day(&self) -> u8 {
+        2
+    }
+
+    fn name(&self) -> &'static str {
+        "Red-Nosed Reports"
+    }
+
+    fn parse(&self, data: &str) -> Result<Self::Input, ParseError> {
+        day_02_parse(data)
+    }
+
+    fn part_1(&self, input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(day_02_part_1(input).into())
+    }
+
+    fn part_2(&self, input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(day_02_part_2(input).into())
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            data: EXAMPLE,
+            part_1: Some("2"),
+            part_2: Some("4"),
+            params: &[],
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     #[test]
     fn test_day_02_part_1() {
-        assert_eq!(day_02_part_1(EXAMPLE), 2);
+        assert_eq!(day_02_part_1(&day_02_parse(EXAMPLE).unwrap()), 2);
     }
 
     #[test]
     fn test_day_02_part_2() {
-        assert_eq!(day_02_part_2(EXAMPLE), 4);
+        assert_eq!(day_02_part_2(&day_02_parse(EXAMPLE).unwrap()), 4);
+    }
+
+    #[test]
+    fn test_day_02_parse() {
+        assert_eq!(
+            day_02_parse("1 2\n3 4\n").unwrap(),
+            vec![vec![1, 2], vec![3, 4]]
+        );
+        assert!(day_02_parse("1 2\n3\n4 5").is_err());
+        assert!(day_02_parse("1 2\n\n4 5").is_err());
     }
 }
@@ -14,18 +14,23 @@
 use nom::{
     branch::alt,
     bytes::complete::{tag, take, take_while_m_n},
-    combinator::map,
-    error::ParseError,
+    combinator::{map, rest},
     multi::many0,
-    sequence::{preceded, tuple},
+    sequence::{preceded, terminated, tuple},
     IResult, InputIter, InputLength, InputTake, Parser, ToUsize,
 };
 
+use crate::{
+    answer::Answer,
+    parsing::{parse_all, ParseError},
+    solution::{Example, Solution, SolveError},
+};
+
 #[derive(Debug, PartialEq)]
-struct MulInstruction(u16, u16);
+pub struct MulInstruction(u16, u16);
 
 #[derive(Debug, PartialEq)]
-enum Instruction {
+pub enum Instruction {
     Mul(MulInstruction),
     Do,
     Dont,
@@ -67,7 +72,7 @@ fn parse_and_skip_up_to_n<C, F, Input, Output, Error>(
 where
     Input: Clone + InputIter + InputTake + InputLength,
     C: ToUsize,
-    Error: ParseError<Input>,
+    Error: nom::error::ParseError<Input>,
     F: Clone + Parser<Input, Output, Error>,
 {
     let up_to = up_to.to_usize();
@@ -95,13 +100,18 @@ fn parse_instruction(input: &str) -> IResult<&str, Instruction> {
     alt((parse_mul, parse_do, parse_dont))(input)
 }
 
+/// The garbage between two instructions can be as long as the memory.
 fn parse_input_data(input: &str) -> IResult<&str, Vec<Instruction>> {
-    many0(parse_and_skip_up_to_n(1024_usize, parse_instruction))(input)
+    many0(parse_and_skip_up_to_n(usize::MAX, parse_instruction))(input)
 }
 
-pub fn day_03_part_1(data: &str) -> i64 {
-    let (_, instructions) = parse_input_data(data).expect("Failed to parse input data");
+/// The memory is mostly garbage, whatever follows the last instruction
+/// is consumed too.
+pub fn day_03_parse(data: &str) -> Result<Vec<Instruction>, ParseError> {
+    parse_all(3, data, terminated(parse_input_data, rest))
+}
 
+pub fn day_03_part_1(instructions: &[Instruction]) -> i64 {
     instructions
         .iter()
         .map(|instruction| match instruction {
@@ -111,9 +121,7 @@ pub fn day_03_part_1(data: &str) -> i64 {
         .sum()
 }
 
-pub fn day_03_part_2(data: &str) -> i64 {
-    let (_, instructions) = parse_input_data(data).expect("Failed to parse input data");
-
+pub fn day_03_part_2(instructions: &[Instruction]) -> i64 {
     let mut mul_enabled = true;
     let mut sum = 0;
 
@@ -136,15 +144,59 @@ pub fn day_03_part_2(data: &str) -> i64 {
     sum
 }
 
+const EXAMPLE_PART_1: &str =
+    "xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))";
+
+const EXAMPLE_PART_2: &str =
+    "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+
+pub struct Day03;
+
+impl Solution for Day03 {
+    type Input = Vec<Instruction>;
+
+    fn day(&self) -> u8 {
+        3
+    }
+
+    fn name(&self) -> &'static str {
+        "Mull It Over"
+    }
+
+    fn parse(&self, data: &str) -> Result<Self::Input, ParseError> {
+        day_03_parse(data)
+    }
+
+    fn part_1(&self, input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(day_03_part_1(input).into())
+    }
+
+    fn part_2(&self, input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(day_03_part_2(input).into())
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                data: EXAMPLE_PART_1,
+                part_1: Some("161"),
+                part_2: Some("161"),
+                params: &[],
+            },
+            Example {
+                data: EXAMPLE_PART_2,
+                part_1: None,
+                part_2: Some("48"),
+                params: &[],
+            },
+        ]
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const EXAMPLE_PART_1: &str =
-        "xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))";
-    const EXAMPLE_PART_2: &str =
-        "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
-
     #[test]
     fn test_mul_number() {
         assert_eq!(mul_number("1"), Ok(("", 1)));
@@ -229,15 +281,24 @@ mod tests {
 
     #[test]
     fn test_day_03_part_1() {
-        assert_eq!(day_03_part_1("mul(2,4)"), 8);
-        assert_eq!(day_03_part_1("xmul(2,4)"), 8);
-        assert_eq!(day_03_part_1("xmul(2,4)%&mul(3,7)"), 29);
-        assert_eq!(day_03_part_1(EXAMPLE_PART_1), 161);
+        assert_eq!(day_03_part_1(&day_03_parse("mul(2,4)").unwrap()), 8);
+        assert_eq!(day_03_part_1(&day_03_parse("xmul(2,4)").unwrap()), 8);
+        assert_eq!(
+            day_03_part_1(&day_03_parse("xmul(2,4)%&mul(3,7)").unwrap()),
+            29
+        );
+        assert_eq!(day_03_part_1(&day_03_parse(EXAMPLE_PART_1).unwrap()), 161);
+    }
+
+    #[test]
+    fn test_day_03_long_garbage() {
+        let data = format!("mul(2,3){}mul(5,5){}", "x".repeat(2000), "y".repeat(2000));
+        assert_eq!(day_03_part_1(&day_03_parse(&data).unwrap()), 31);
     }
 
     #[test]
     fn test_day_03_part_2() {
-        assert_eq!(day_03_part_2(EXAMPLE_PART_1), 161);
-        assert_eq!(day_03_part_2(EXAMPLE_PART_2), 48);
+        assert_eq!(day_03_part_2(&day_03_parse(EXAMPLE_PART_1).unwrap()), 161);
+        assert_eq!(day_03_part_2(&day_03_parse(EXAMPLE_PART_2).unwrap()), 48);
     }
 }
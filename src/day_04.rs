@@ -12,23 +12,17 @@
 */
 //use ndarray::prelude::*;
 use ndarray::{Array2 /*,  Axis*/};
-use nom::{
-    character::complete::{line_ending, one_of},
-    combinator::map,
-    multi::{many1, separated_list1},
-    IResult,
+use nom::IResult;
+
+use crate::{
+    answer::Answer,
+    grid::parse_char_grid,
+    parsing::{parse_all, ParseError},
+    solution::{Example, Solution, SolveError},
 };
 
 fn parse_input_data(data: &str) -> IResult<&str, Array2<char>> {
-    map(
-        separated_list1(line_ending, many1(one_of("XMAS.0123456789BCDEF"))),
-        |rows| {
-            let nb_rows = rows.len();
-            let nb_cols = rows.first().map_or(0, |row| row.len());
-
-            Array2::from_shape_fn((nb_rows, nb_cols), |(row, col)| rows[row][col])
-        },
-    )(data)
+    parse_char_grid("XMAS.0123456789BCDEF")(data)
 }
 
 // Infortunately, the ndarray in rust doesn't take a offset/k parameter
@@ -95,9 +89,11 @@ fn extract_strings(grid: &Array2<char>, min_string_size: usize) -> Vec<String> {
     strings
 }*/
 
-pub fn day_04_part_1(data: &str) -> i64 {
-    let (_, grid) = parse_input_data(data).expect("Failed to parse input data");
+pub fn day_04_parse(data: &str) -> Result<Array2<char>, ParseError> {
+    parse_all(4, data, parse_input_data)
+}
 
+pub fn day_04_part_1(grid: &Array2<char>) -> i64 {
     /*let strings = extract_strings(&grid, 4);
 
     strings
@@ -161,9 +157,7 @@ pub fn day_04_part_1(data: &str) -> i64 {
             .sum::<usize>()) as i64
 }
 
-pub fn day_04_part_2(data: &str) -> i64 {
-    let (_, grid) = parse_input_data(data).expect("Failed to parse input data");
-
+pub fn day_04_part_2(grid: &Array2<char>) -> i64 {
     grid.windows((3, 3))
         .into_iter()
         .filter(|w| {
@@ -216,17 +210,7 @@ pub fn day_04_part_2(data: &str) -> i64 {
         .count() as i64
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const EXAMPLE_SMALL: &str = "..X...
-.SAMX.
-.A..A.
-XMAS.S
-.X....";
-
-    const EXAMPLE_BIG: &str = "MMMSXXMASM
+const EXAMPLE_BIG: &str = "MMMSXXMASM
 MSAMXMSMSA
 AMXSXMAAMM
 MSAMASMSMX
@@ -237,6 +221,51 @@ SAXAMASAAA
 MAMMMXMMMM
 MXMXAXMASX";
 
+pub struct Day04;
+
+impl Solution for Day04 {
+    type Input = Array2<char>;
+
+    fn day(&self) -> u8 {
+        4
+    }
+
+    fn name(&self) -> &'static str {
+        "Ceres Search"
+    }
+
+    fn parse(&self, data: &str) -> Result<Self::Input, ParseError> {
+        day_04_parse(data)
+    }
+
+    fn part_1(&self, input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(day_04_part_1(input).into())
+    }
+
+    fn part_2(&self, input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(day_04_part_2(input).into())
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            data: EXAMPLE_BIG,
+            part_1: Some("18"),
+            part_2: Some("9"),
+            params: &[],
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE_SMALL: &str = "..X...
+.SAMX.
+.A..A.
+XMAS.S
+.X....";
+
     const EXAMPLE_PART_2: &str = ".M.S......
 ..A..MSMS.
 .M.S.MAA..
@@ -250,17 +279,20 @@ M.M.M.M.M.
 
     #[test]
     fn test_day_04_part_1() {
-        assert_eq!(day_04_part_1("01\n23\n45\n67"), 0);
-        assert_eq!(day_04_part_1("0123\n4567"), 0);
-        assert_eq!(day_04_part_1("012\n345\n678"), 0);
-        assert_eq!(day_04_part_1("0123\n4567\n89AB\nCDEF"), 0);
-        assert_eq!(day_04_part_1(EXAMPLE_SMALL), 4);
-        assert_eq!(day_04_part_1(EXAMPLE_BIG), 18);
+        assert_eq!(day_04_part_1(&day_04_parse("01\n23\n45\n67").unwrap()), 0);
+        assert_eq!(day_04_part_1(&day_04_parse("0123\n4567").unwrap()), 0);
+        assert_eq!(day_04_part_1(&day_04_parse("012\n345\n678").unwrap()), 0);
+        assert_eq!(
+            day_04_part_1(&day_04_parse("0123\n4567\n89AB\nCDEF").unwrap()),
+            0
+        );
+        assert_eq!(day_04_part_1(&day_04_parse(EXAMPLE_SMALL).unwrap()), 4);
+        assert_eq!(day_04_part_1(&day_04_parse(EXAMPLE_BIG).unwrap()), 18);
     }
 
     #[test]
     fn test_day_04_part_2() {
-        assert_eq!(day_04_part_2(EXAMPLE_PART_2), 9);
-        assert_eq!(day_04_part_2(EXAMPLE_BIG), 9);
+        assert_eq!(day_04_part_2(&day_04_parse(EXAMPLE_PART_2).unwrap()), 9);
+        assert_eq!(day_04_part_2(&day_04_parse(EXAMPLE_BIG).unwrap()), 9);
     }
 }
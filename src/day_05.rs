@@ -23,8 +23,14 @@ use nom::{
     multi::separated_list1, sequence::tuple, IResult,
 };
 
+use crate::{
+    answer::Answer,
+    parsing::{parse_all, ParseError},
+    solution::{Example, Solution, SolveError},
+};
+
 #[derive(Debug)]
-struct Data {
+pub struct Data {
     rules: Vec<(u8, u8)>,
     updates: Vec<Vec<u8>>,
 }
@@ -88,7 +94,7 @@ fn build_faster_rules(rules: &[(u8, u8)]) -> [Vec<u8>; 256] {
 // We are going for a Kahn's algorithm approach. I started to come up with
 // something looking vaguely like it, but it sounded hard, so I checked the solution.
 // Sorry.
-fn kahn_algorithm(numbers: &[u8], rules: &[(u8, u8)]) -> Vec<u8> {
+fn kahn_algorithm(numbers: &[u8], rules: &[(u8, u8)]) -> Result<Vec<u8>, SolveError> {
     let mut graph: [Vec<u8>; 256] = std::array::from_fn(|_| Vec::new());
     let mut in_degree: [u8; 256] = [0; 256];
 
@@ -112,8 +118,7 @@ fn kahn_algorithm(numbers: &[u8], rules: &[(u8, u8)]) -> Vec<u8> {
 
     let mut sorted_order = Vec::with_capacity(numbers.len());
 
-    while !queue.is_empty() {
-        let current = queue.pop_front().expect("Queue is empty !");
+    while let Some(current) = queue.pop_front() {
         sorted_order.push(current);
 
         for next_page in &graph[current as usize] {
@@ -125,15 +130,20 @@ fn kahn_algorithm(numbers: &[u8], rules: &[(u8, u8)]) -> Vec<u8> {
     }
 
     if sorted_order.len() != numbers.len() {
-        panic!("We didn't find all the pages ! A cycle is present !");
+        return Err(SolveError::Unsupported(format!(
+            "the rules of the update {:?} have a cycle",
+            numbers
+        )));
     }
 
-    sorted_order
+    Ok(sorted_order)
 }
 
-pub fn day_05_part_1(data: &str) -> i64 {
-    let (_, data) = parse_input_data(data).expect("Failed to parse input data");
+pub fn day_05_parse(data: &str) -> Result<Data, ParseError> {
+    parse_all(5, data, parse_input_data)
+}
 
+pub fn day_05_part_1(data: &Data) -> i64 {
     // We create an array that for each page, contains the list of pages that should come before
     let faster_rules = build_faster_rules(&data.rules);
 
@@ -144,26 +154,20 @@ pub fn day_05_part_1(data: &str) -> i64 {
         .sum()
 }
 
-pub fn day_05_part_2(data: &str) -> i64 {
-    let (_, data) = parse_input_data(data).expect("Failed to parse input data");
-
+pub fn day_05_part_2(data: &Data) -> Result<i64, SolveError> {
     let faster_rules = build_faster_rules(&data.rules);
 
     data.updates
         .iter()
         .filter(|update| !is_valid_update(update, &faster_rules))
         .map(|update| {
-            let sorted_update = kahn_algorithm(update, &data.rules);
-            sorted_update[sorted_update.len() / 2] as i64
+            let sorted_update = kahn_algorithm(update, &data.rules)?;
+            Ok(sorted_update[sorted_update.len() / 2] as i64)
         })
         .sum()
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const EXAMPLE: &str = "47|53
+const EXAMPLE: &str = "47|53
 97|13
 97|61
 97|47
@@ -192,13 +196,61 @@ mod tests {
 61,13,29
 97,13,75,29,47";
 
+pub struct Day05;
+
+impl Solution for Day05 {
+    type Input = Data;
+
+    fn day(&self) -> u8 {
+        5
+    }
+
+    fn name(&self) -> &'static str {
+        "Print Queue"
+    }
+
+    fn parse(&self, data: &str) -> Result<Self::Input, ParseError> {
+        day_05_parse(data)
+    }
+
+    fn part_1(&self, input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(day_05_part_1(input).into())
+    }
+
+    fn part_2(&self, input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(day_05_part_2(input)?.into())
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            data: EXAMPLE,
+            part_1: Some("143"),
+            part_2: Some("123"),
+            params: &[],
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     #[test]
     fn test_day_05_part_1() {
-        assert_eq!(day_05_part_1(EXAMPLE), 143);
+        assert_eq!(day_05_part_1(&day_05_parse(EXAMPLE).unwrap()), 143);
     }
 
     #[test]
     fn test_day_05_part_2() {
-        assert_eq!(day_05_part_2(EXAMPLE), 123);
+        assert_eq!(day_05_part_2(&day_05_parse(EXAMPLE).unwrap()), Ok(123));
+    }
+
+    #[test]
+    fn test_day_05_part_2_cycle() {
+        let data = day_05_parse("1|2\n2|1\n\n1,2").unwrap();
+        assert!(matches!(
+            day_05_part_2(&data),
+            Err(SolveError::Unsupported(_))
+        ));
     }
 }
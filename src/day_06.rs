@@ -22,16 +22,19 @@
 */
 
 use ndarray::{Array2, Array3};
-use nom::{
-    character::complete::{line_ending, one_of},
-    combinator::map,
-    multi::{many1, separated_list1},
-    IResult,
-};
+use nom::IResult;
 use rayon::iter::{ParallelBridge, ParallelIterator};
 
+use crate::{
+    answer::Answer,
+    direction::Direction,
+    grid::{find_cell, parse_cells, render_with, GridCell},
+    parsing::{parse_all, ParseError},
+    solution::{Example, Solution, SolveError},
+};
+
 #[derive(Clone, Debug, PartialEq)]
-enum Space {
+pub enum Space {
     Empty,
     Obstructed,
     Start,
@@ -39,120 +42,46 @@ enum Space {
     Visited,
 }
 
-#[derive(Debug, PartialEq)]
-enum Direction {
-    Up,
-    Right,
-    Down,
-    Left,
-}
-
-impl Direction {
-    fn rotate(&self) -> Self {
-        match self {
-            Direction::Up => Direction::Right,
-            Direction::Right => Direction::Down,
-            Direction::Down => Direction::Left,
-            Direction::Left => Direction::Up,
-        }
-    }
+pub type Map = Array2<Space>;
 
-    fn facing_position(
-        &self,
-        position: (usize, usize),
-        map_size: (usize, usize),
-    ) -> Option<(usize, usize)> {
-        let (row, col) = position;
-        let (nb_rows, nb_cols) = map_size;
-        match (self, row, col) {
-            (Direction::Up, 0, _) => None,
-            (Direction::Up, _, _) => Some((row - 1, col)),
-            (Direction::Right, _, _) if col == nb_cols - 1 => None,
-            (Direction::Right, _, _) => Some((row, col + 1)),
-            (Direction::Down, _, _) if row == nb_rows - 1 => None,
-            (Direction::Down, _, _) => Some((row + 1, col)),
-            (Direction::Left, _, 0) => None,
-            (Direction::Left, _, _) => Some((row, col - 1)),
+impl GridCell for Space {
+    fn from_char(c: char) -> Option<Self> {
+        match c {
+            '.' => Some(Space::Empty),
+            '#' => Some(Space::Obstructed),
+            '^' => Some(Space::Start),
+            _ => None,
         }
     }
 
-    fn to_usize(&self) -> usize {
+    fn to_char(&self) -> char {
         match self {
-            Direction::Up => 0,
-            Direction::Right => 1,
-            Direction::Down => 2,
-            Direction::Left => 3,
+            Space::Empty => '.',
+            Space::Obstructed => '#',
+            Space::Start => '^',
+            Space::Visited => 'X',
         }
     }
 }
 
-type Map = Array2<Space>;
-
 #[allow(dead_code)]
-trait MapExt {
-    fn print(&self);
-    fn print_witf_obstacle_position(&self, obstacle_position: (usize, usize));
-}
-
-impl MapExt for Map {
-    fn print(&self) {
-        for row in self.rows() {
-            for space in row {
-                print!(
-                    "{}",
-                    match space {
-                        Space::Empty => '.',
-                        Space::Obstructed => '#',
-                        Space::Start => '^',
-                        Space::Visited => 'X',
-                    }
-                );
-            }
-            println!();
-        }
-    }
-
-    fn print_witf_obstacle_position(&self, obstacle_position: (usize, usize)) {
-        for ((row, col), space) in self.indexed_iter() {
-            if (row, col) == obstacle_position {
-                print!("O");
-            } else {
-                print!(
-                    "{}",
-                    match space {
-                        Space::Empty => '.',
-                        Space::Obstructed => '#',
-                        Space::Start => '^',
-                        Space::Visited => 'X',
-                    }
-                );
-            }
-            if col == self.ncols() - 1 {
-                println!();
-            }
-        }
-    }
+fn print_with_obstacle_position(map: &Map, obstacle_position: (usize, usize)) {
+    print!(
+        "{}",
+        render_with(map, |position, space| if position == obstacle_position {
+            'O'
+        } else {
+            space.to_char()
+        })
+    );
 }
 
 fn parse_input_data(data: &str) -> IResult<&str, Map> {
-    map(separated_list1(line_ending, many1(one_of(".#^"))), |rows| {
-        let nb_rows = rows.len();
-        let nb_cols = rows.first().map_or(0, |row| row.len());
-
-        Array2::from_shape_fn((nb_rows, nb_cols), |(row, col)| match rows[row][col] {
-            '.' => Space::Empty,
-            '#' => Space::Obstructed,
-            '^' => Space::Start,
-            _ => unreachable!(),
-        })
-    })(data)
+    parse_cells(data)
 }
 
-fn find_start_position(map: &Map) -> (usize, usize) {
-    map.indexed_iter()
-        .find(|(_, m)| **m == Space::Start)
-        .expect("No start position found")
-        .0
+fn find_start_position(map: &Map) -> Result<(usize, usize), SolveError> {
+    find_cell(map, &Space::Start).ok_or(SolveError::MissingCell("start"))
 }
 
 fn visit_map(map: &Map, start_position: (usize, usize)) -> Map {
@@ -165,9 +94,9 @@ fn visit_map(map: &Map, start_position: (usize, usize)) -> Map {
 
     map[position] = Space::Visited;
 
-    while let Some(next_position) = direction.facing_position(position, shape) {
+    while let Some(next_position) = direction.step(shape, position) {
         if map[next_position] == Space::Obstructed {
-            direction = direction.rotate();
+            direction = direction.rotate_cw();
         } else {
             position = next_position;
             map[position] = Space::Visited;
@@ -177,17 +106,19 @@ fn visit_map(map: &Map, start_position: (usize, usize)) -> Map {
     map
 }
 
-pub fn day_06_part_1(data: &str) -> i64 {
-    let (_, map) = parse_input_data(data).expect("Failed to parse input data");
+pub fn day_06_parse(data: &str) -> Result<Map, ParseError> {
+    parse_all(6, data, parse_input_data)
+}
 
-    let start_position = find_start_position(&map);
-    let visited_map = visit_map(&map, start_position);
+pub fn day_06_part_1(map: &Map) -> Result<i64, SolveError> {
+    let start_position = find_start_position(map)?;
+    let visited_map = visit_map(map, start_position);
 
-    // map.print();
-    visited_map
+    // print!("{}", render(&visited_map));
+    Ok(visited_map
         .iter()
         .filter(|space| **space == Space::Visited)
-        .count() as i64
+        .count() as i64)
 }
 
 /*
@@ -262,12 +193,11 @@ struct LookupTables {
 }
 
 fn print_lookup_table(lookup_table: &Array2<Option<usize>>) {
-    for row in lookup_table.rows() {
-        for cell in row {
-            print!("{}", cell.map_or('.', |index| (index as u8 + b'0') as char));
-        }
-        println!();
-    }
+    print!(
+        "{}",
+        render_with(lookup_table, |_, cell| cell
+            .map_or('.', |index| (index as u8 + b'0') as char))
+    );
 }
 
 impl LookupTables {
@@ -294,7 +224,7 @@ impl LookupTables {
 
     fn position_before_obstacle(
         &self,
-        direction: &Direction,
+        direction: Direction,
         position: (usize, usize),
     ) -> Option<(usize, usize)> {
         let (row, col) = position;
@@ -358,54 +288,52 @@ fn will_exit_map(
 
     let mut position = start_position;
     let mut direction = Direction::Up;
-    visited_positions[(position.0, position.1, direction.to_usize())] = true;
+    visited_positions[(position.0, position.1, direction.index())] = true;
     // if obstacle straight above the start position, it's a special case
     // and we need to rotate immediately
     if lookup_tables.up[start_position] == Some(start_position.0 - 1) {
-        direction = direction.rotate();
+        direction = direction.rotate_cw();
         // if obstacle on the right then, rotate again
         if lookup_tables.right[start_position] == Some(start_position.1 + 1) {
-            direction = direction.rotate();
+            direction = direction.rotate_cw();
             // and again for the bottom
             if lookup_tables.down[start_position] == Some(start_position.0 + 1) {
-                direction = direction.rotate();
+                direction = direction.rotate_cw();
                 // and if another obstacle, it's a dead end
                 if lookup_tables.left[start_position] == Some(start_position.1 - 1) {
                     return false;
                 }
             }
         }
-        visited_positions[(position.0, position.1, direction.to_usize())] = true;
+        visited_positions[(position.0, position.1, direction.index())] = true;
     }
 
     while let Some(position_before_next_obstacle) =
-        lookup_tables.position_before_obstacle(&direction, position)
+        lookup_tables.position_before_obstacle(direction, position)
     {
         position = position_before_next_obstacle;
-        let visited_index = (position.0, position.1, direction.to_usize());
+        let visited_index = (position.0, position.1, direction.index());
         if visited_positions[visited_index] {
             return false;
         }
         visited_positions[visited_index] = true;
-        direction = direction.rotate();
+        direction = direction.rotate_cw();
     }
 
     true
 }
 
-pub fn day_06_part_2(data: &str) -> i64 {
-    let (_, map) = parse_input_data(data).expect("Failed to parse input data");
-
+pub fn day_06_part_2(map: &Map) -> Result<i64, SolveError> {
     // we build lookup maps that give the index of the next obstacle in each direction
-    let lookup_tables = LookupTables::new(&map);
+    let lookup_tables = LookupTables::new(map);
 
-    let start_position = find_start_position(&map);
+    let start_position = find_start_position(map)?;
 
-    let visited_map = visit_map(&map, start_position);
+    let visited_map = visit_map(map, start_position);
 
     let map_size = (map.nrows(), map.ncols());
 
-    visited_map
+    Ok(visited_map
         .indexed_iter()
         .filter(|(position, space)| **space == Space::Visited && position != &start_position)
         .par_bridge()
@@ -413,14 +341,10 @@ pub fn day_06_part_2(data: &str) -> i64 {
             let new_lookup_tables = lookup_tables.with_new_obstacle(*position);
             !will_exit_map(map_size, start_position, &new_lookup_tables)
         })
-        .count() as i64
+        .count() as i64)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const EXAMPLE: &str = "....#.....
+const EXAMPLE: &str = "....#.....
 .........#
 ..........
 ..#.......
@@ -431,13 +355,52 @@ mod tests {
 #.........
 ......#...";
 
+pub struct Day06;
+
+impl Solution for Day06 {
+    type Input = Map;
+
+    fn day(&self) -> u8 {
+        6
+    }
+
+    fn name(&self) -> &'static str {
+        "Guard Gallivant"
+    }
+
+    fn parse(&self, data: &str) -> Result<Self::Input, ParseError> {
+        day_06_parse(data)
+    }
+
+    fn part_1(&self, input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(day_06_part_1(input)?.into())
+    }
+
+    fn part_2(&self, input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(day_06_part_2(input)?.into())
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            data: EXAMPLE,
+            part_1: Some("41"),
+            part_2: Some("6"),
+            params: &[],
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     #[test]
     fn test_day_06_part_1() {
-        assert_eq!(day_06_part_1(EXAMPLE), 41);
+        assert_eq!(day_06_part_1(&day_06_parse(EXAMPLE).unwrap()), Ok(41));
     }
 
     #[test]
     fn test_day_06_part_2() {
-        assert_eq!(day_06_part_2(EXAMPLE), 6);
+        assert_eq!(day_06_part_2(&day_06_parse(EXAMPLE).unwrap()), Ok(6));
     }
 }
@@ -17,6 +17,12 @@ use nom::{
 };
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 
+use crate::{
+    answer::Answer,
+    parsing::{parse_all, ParseError},
+    solution::{Example, Solution, SolveError},
+};
+
 fn rec_look_for_solutions(current: u64, target: u64, numbers: &[u64], concat: bool) -> bool {
     if let Some((last, rest)) = numbers.split_last() {
         // If we can substract
@@ -25,13 +31,16 @@ fn rec_look_for_solutions(current: u64, target: u64, numbers: &[u64], concat: bo
         }
 
         // If we can devide
-        if current % *last == 0 && rec_look_for_solutions(current / last, target, rest, concat) {
+        if *last != 0
+            && current.is_multiple_of(*last)
+            && rec_look_for_solutions(current / last, target, rest, concat)
+        {
             return true;
         }
 
         if concat {
             // fancy way to get a base 10 number with the same place value
-            let place_value = 10_u64.pow(last.ilog10() + 1);
+            let place_value = 10_u64.pow(last.checked_ilog10().unwrap_or(0) + 1);
             // If it finishes with the last number, it's potentially a concatenation
             if current % place_value == *last {
                 return rec_look_for_solutions(current / place_value, target, rest, concat);
@@ -44,10 +53,10 @@ fn rec_look_for_solutions(current: u64, target: u64, numbers: &[u64], concat: bo
 }
 
 fn is_valid_case(target: u64, numbers: &[u64], concat: bool) -> bool {
-    if numbers.is_empty() {
-        panic!("We need at least one number");
+    match numbers.split_first() {
+        Some((first, rest)) => rec_look_for_solutions(target, *first, rest, concat),
+        None => false,
     }
-    rec_look_for_solutions(target, numbers[0], &numbers[1..], concat)
 }
 
 fn parse_input_data(data: &str) -> IResult<&str, Vec<(u64, Vec<u64>)>> {
@@ -64,9 +73,11 @@ fn parse_input_data(data: &str) -> IResult<&str, Vec<(u64, Vec<u64>)>> {
     )(data)
 }
 
-pub fn day_07_part_1(data: &str) -> i64 {
-    let (_, data) = parse_input_data(data).expect("Failed to parse input data");
+pub fn day_07_parse(data: &str) -> Result<Vec<(u64, Vec<u64>)>, ParseError> {
+    parse_all(7, data, parse_input_data)
+}
 
+pub fn day_07_part_1(data: &[(u64, Vec<u64>)]) -> i64 {
     data.par_iter()
         //.skip(849)
         .filter(|(target, numbers)| is_valid_case(*target, numbers, false))
@@ -74,20 +85,14 @@ pub fn day_07_part_1(data: &str) -> i64 {
         .sum::<u64>() as i64
 }
 
-pub fn day_07_part_2(data: &str) -> i64 {
-    let (_, data) = parse_input_data(data).expect("Failed to parse input data");
-
+pub fn day_07_part_2(data: &[(u64, Vec<u64>)]) -> i64 {
     data.par_iter()
         .filter(|(target, numbers)| is_valid_case(*target, numbers, true))
         .map(|(target, _)| *target)
         .sum::<u64>() as i64
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const EXAMPLE: &str = "190: 10 19
+const EXAMPLE: &str = "190: 10 19
 3267: 81 40 27
 83: 17 5
 156: 15 6
@@ -97,13 +102,59 @@ mod tests {
 21037: 9 7 18 13
 292: 11 6 16 20";
 
+pub struct Day07;
+
+impl Solution for Day07 {
+    type Input = Vec<(u64, Vec<u64>)>;
+
+    fn day(&self) -> u8 {
+        7
+    }
+
+    fn name(&self) -> &'static str {
+        "Bridge Repair"
+    }
+
+    fn parse(&self, data: &str) -> Result<Self::Input, ParseError> {
+        day_07_parse(data)
+    }
+
+    fn part_1(&self, input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(day_07_part_1(input).into())
+    }
+
+    fn part_2(&self, input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(day_07_part_2(input).into())
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            data: EXAMPLE,
+            part_1: Some("3749"),
+            part_2: Some("11387"),
+            params: &[],
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     #[test]
     fn test_day_07_part_1() {
-        assert_eq!(day_07_part_1(EXAMPLE), 3749);
+        assert_eq!(day_07_part_1(&day_07_parse(EXAMPLE).unwrap()), 3749);
     }
 
     #[test]
     fn test_day_07_part_2() {
-        assert_eq!(day_07_part_2(EXAMPLE), 11387);
+        assert_eq!(day_07_part_2(&day_07_parse(EXAMPLE).unwrap()), 11387);
+    }
+
+    #[test]
+    fn test_day_07_zeros() {
+        let data = day_07_parse("0: 0 0\n5: 5 0\n50: 5 0").unwrap();
+        assert_eq!(day_07_part_1(&data), 5);
+        assert_eq!(day_07_part_2(&data), 55);
     }
 }
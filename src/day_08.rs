@@ -12,15 +12,17 @@ use itertools::Itertools;
 use ndarray::Array2;
 use nom::{
     branch::alt,
-    character::{
-        complete::{line_ending, satisfy},
-        is_alphanumeric,
-    },
-    combinator::map,
-    multi::{many1, separated_list1},
+    character::{complete::satisfy, is_alphanumeric},
     IResult,
 };
 
+use crate::{
+    answer::Answer,
+    grid::{offset_position, parse_grid, render_with},
+    parsing::{parse_all, ParseError},
+    solution::{Example, Solution, SolveError},
+};
+
 fn ascii_to_key(ascii: char) -> Option<usize> {
     match ascii {
         'A'..='Z' => Some(ascii as usize - 65),
@@ -33,33 +35,20 @@ fn ascii_to_key(ascii: char) -> Option<usize> {
 
 #[allow(dead_code)]
 fn print_presence_map(map: &Array2<bool>) {
-    for row in map.outer_iter() {
-        for presence in row {
-            print!("{}", if *presence { '#' } else { '.' });
-        }
-        println!();
-    }
+    print!(
+        "{}",
+        render_with(map, |_, &presence| if presence { '#' } else { '.' })
+    );
 }
 
 fn parse_input_data(data: &str) -> IResult<&str, Array2<char>> {
-    map(
-        separated_list1(
-            line_ending,
-            many1(alt((
-                satisfy(|c| is_alphanumeric(c as u8)),
-                nom::character::complete::char('.'),
-            ))),
-        ),
-        |rows| {
-            let nb_rows = rows.len();
-            let nb_cols = rows.first().map_or(0, |row| row.len());
-
-            Array2::from_shape_fn((nb_rows, nb_cols), |(row, col)| rows[row][col])
-        },
-    )(data)
+    parse_grid(alt((
+        satisfy(|c| is_alphanumeric(c as u8)),
+        nom::character::complete::char('.'),
+    )))(data)
 }
 
-fn compute_solution(grid: Array2<char>, harmonics: bool) -> i64 {
+fn compute_solution(grid: &Array2<char>, harmonics: bool) -> i64 {
     const NB_KEYS: usize = 62;
     let mut antennas_per_key: Vec<Vec<(usize, usize)>> = vec![Vec::new(); NB_KEYS];
 
@@ -70,8 +59,6 @@ fn compute_solution(grid: Array2<char>, harmonics: bool) -> i64 {
     }
 
     let mut antinodes_presence_map = Array2::from_elem(grid.dim(), false);
-    let nb_cols = grid.ncols() as i64;
-    let nb_rows = grid.nrows() as i64;
 
     for antennas_group in antennas_per_key.iter() {
         if antennas_group.is_empty() {
@@ -82,38 +69,24 @@ fn compute_solution(grid: Array2<char>, harmonics: bool) -> i64 {
         //println!("{:?}", antennas_group);
 
         for window in antennas_group.iter().permutations(2) {
-            let (col_a, row_a) = window[0];
-            let (col_b, row_b) = window[1];
+            let antenna_a = *window[0];
+            let antenna_b = *window[1];
 
             // the antenna is also an antinode in harmonics mode
             if harmonics {
-                antinodes_presence_map[(*col_a, *row_a)] = true;
+                antinodes_presence_map[antenna_a] = true;
             }
             //println!("{:?} {:?}", window[0], window[1]);
-            let col_a = *col_a as i64;
-            let row_a = *row_a as i64;
-            let col_b = *col_b as i64;
-            let row_b = *row_b as i64;
-
-            let diff_col = col_b - col_a;
-            let diff_row = row_b - row_a;
-
-            let mut antipod_col = col_a; // - diff_col;
-            let mut antipod_row = row_a; // - diff_row;
-
-            loop {
-                antipod_col -= diff_col;
-                antipod_row -= diff_row;
-
-                if antipod_col < 0
-                    || antipod_row < 0
-                    || antipod_col >= nb_cols
-                    || antipod_row >= nb_rows
-                {
-                    break;
-                }
+            let diff = (
+                antenna_a.0 as isize - antenna_b.0 as isize,
+                antenna_a.1 as isize - antenna_b.1 as isize,
+            );
 
-                antinodes_presence_map[(antipod_col as usize, antipod_row as usize)] = true;
+            let mut antipod = antenna_a;
+
+            while let Some(next_antipod) = offset_position(grid.dim(), antipod, diff) {
+                antipod = next_antipod;
+                antinodes_presence_map[antipod] = true;
 
                 if !harmonics {
                     break;
@@ -131,23 +104,19 @@ fn compute_solution(grid: Array2<char>, harmonics: bool) -> i64 {
         .count() as i64
 }
 
-pub fn day_08_part_1(data: &str) -> i64 {
-    let (_, grid) = parse_input_data(data).expect("Failed to parse input data");
+pub fn day_08_parse(data: &str) -> Result<Array2<char>, ParseError> {
+    parse_all(8, data, parse_input_data)
+}
 
+pub fn day_08_part_1(grid: &Array2<char>) -> i64 {
     compute_solution(grid, false)
 }
 
-pub fn day_08_part_2(data: &str) -> i64 {
-    let (_, grid) = parse_input_data(data).expect("Failed to parse input data");
-
+pub fn day_08_part_2(grid: &Array2<char>) -> i64 {
     compute_solution(grid, true)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const EXAMPLE: &str = "............
+const EXAMPLE: &str = "............
 ........0...
 .....0......
 .......0....
@@ -160,11 +129,51 @@ mod tests {
 ............
 ............";
 
+pub struct Day08;
+
+impl Solution for Day08 {
+    type Input = Array2<char>;
+
+    fn day(&self) -> u8 {
+        8
+    }
+
+    fn name(&self) -> &'static str {
+        "Resonant Collinearity"
+    }
+
+    fn parse(&self, data: &str) -> Result<Self::Input, ParseError> {
+        day_08_parse(data)
+    }
+
+    fn part_1(&self, input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(day_08_part_1(input).into())
+    }
+
+    fn part_2(&self, input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(day_08_part_2(input).into())
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            data: EXAMPLE,
+            part_1: Some("14"),
+            part_2: Some("34"),
+            params: &[],
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     #[test]
     fn test_day_08_part_1() {
         assert_eq!(
             day_08_part_1(
-                "..........
+                &day_08_parse(
+                    "..........
 ..........
 ..........
 ....a.....
@@ -174,12 +183,15 @@ mod tests {
 ..........
 ..........
 .........."
+                )
+                .unwrap()
             ),
             2
         );
         assert_eq!(
             day_08_part_1(
-                "..........
+                &day_08_parse(
+                    "..........
 ..........
 ..........
 ....a.....
@@ -189,17 +201,20 @@ mod tests {
 ..........
 ..........
 .........."
+                )
+                .unwrap()
             ),
             4
         );
-        assert_eq!(day_08_part_1(EXAMPLE), 14);
+        assert_eq!(day_08_part_1(&day_08_parse(EXAMPLE).unwrap()), 14);
     }
 
     #[test]
     fn test_day_08_part_2() {
         assert_eq!(
             day_08_part_2(
-                "T.........
+                &day_08_parse(
+                    "T.........
 ...T......
 .T........
 ..........
@@ -209,9 +224,11 @@ mod tests {
 ..........
 ..........
 .........."
+                )
+                .unwrap()
             ),
             9
         );
-        assert_eq!(day_08_part_2(EXAMPLE), 34);
+        assert_eq!(day_08_part_2(&day_08_parse(EXAMPLE).unwrap()), 34);
     }
 }
@@ -25,12 +25,21 @@ use nom::{
     IResult,
 };
 
+use crate::{
+    answer::Answer,
+    parsing::{parse_all, ParseError},
+    solution::{Example, Solution, SolveError},
+};
+
 fn parse_input_data(data: &str) -> IResult<&str, Vec<u8>> {
     many1(map(satisfy(|c| is_digit(c as u8)), |c| c as u8 - b'0'))(data)
 }
 
-pub fn day_09_part_1(data: &str) -> i64 {
-    let (_, numbers) = parse_input_data(data).expect("Failed to parse input data");
+pub fn day_09_parse(data: &str) -> Result<Vec<u8>, ParseError> {
+    parse_all(9, data, parse_input_data)
+}
+
+pub fn day_09_part_1(numbers: &[u8]) -> i64 {
     //println!("{:?}", numbers);
 
     let sum = numbers.iter().map(|n| *n as usize).sum::<usize>();
@@ -42,8 +51,8 @@ pub fn day_09_part_1(data: &str) -> i64 {
         let files_size = chunk[0] as usize;
         let free_space_size = *chunk.get(1).unwrap_or(&0) as usize;
 
-        memory.extend(std::iter::repeat(Some(i)).take(files_size));
-        memory.extend(std::iter::repeat(None).take(free_space_size));
+        memory.extend(std::iter::repeat_n(Some(i), files_size));
+        memory.extend(std::iter::repeat_n(None, free_space_size));
 
         final_size += files_size;
     }
@@ -138,14 +147,13 @@ fn fit_in_new_index(
     }
 }
 
-pub fn day_09_part_2(data: &str) -> i64 {
-    let (_, numbers) = parse_input_data(data).expect("Failed to parse input data");
+pub fn day_09_part_2(numbers: &[u8]) -> i64 {
     //println!("len: {}", numbers.len());
 
     // VecDeque<index_start>
     let mut free_space_per_size: [VecDeque<usize>; 9] = Default::default();
     // Vec<(id, index_start, size)>
-    let mut used_blocs: Vec<(usize, usize, usize)> = Vec::with_capacity((numbers.len() + 1) / 2);
+    let mut used_blocs: Vec<(usize, usize, usize)> = Vec::with_capacity(numbers.len().div_ceil(2));
     let mut index = 0_usize;
 
     for (id, chunk) in numbers.chunks(2).enumerate() {
@@ -162,7 +170,7 @@ pub fn day_09_part_2(data: &str) -> i64 {
         }
     }
 
-    assert_eq!(used_blocs.len(), (numbers.len() + 1) / 2);
+    assert_eq!(used_blocs.len(), numbers.len().div_ceil(2));
 
     //println!("{:?}", used_blocs);
     //println!("{:?}", free_space_per_size);
@@ -201,29 +209,68 @@ pub fn day_09_part_2(data: &str) -> i64 {
     checksum as i64
 }
 
+const EXAMPLE_BIG: &str = "2333133121414131402";
+
+pub struct Day09;
+
+impl Solution for Day09 {
+    type Input = Vec<u8>;
+
+    fn day(&self) -> u8 {
+        9
+    }
+
+    fn name(&self) -> &'static str {
+        "Disk Fragmenter"
+    }
+
+    fn parse(&self, data: &str) -> Result<Self::Input, ParseError> {
+        day_09_parse(data)
+    }
+
+    fn part_1(&self, input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(day_09_part_1(input).into())
+    }
+
+    fn part_2(&self, input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(day_09_part_2(input).into())
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            data: EXAMPLE_BIG,
+            part_1: Some("1928"),
+            part_2: Some("2858"),
+            params: &[],
+        }]
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     const EXAMPLE_SMALL: &str = "12345";
-    const EXAMPLE_BIG: &str = "2333133121414131402";
 
     #[test]
     fn test_day_09_part_1() {
-        assert_eq!(day_09_part_1(EXAMPLE_SMALL), 60);
-        assert_eq!(day_09_part_1(EXAMPLE_BIG), 1928);
+        assert_eq!(day_09_part_1(&day_09_parse(EXAMPLE_SMALL).unwrap()), 60);
+        assert_eq!(day_09_part_1(&day_09_parse(EXAMPLE_BIG).unwrap()), 1928);
     }
 
     #[test]
     fn test_day_09_part_2() {
-        assert_eq!(day_09_part_2(EXAMPLE_SMALL), 132);
-        assert_eq!(day_09_part_2(EXAMPLE_BIG), 2858);
+        assert_eq!(day_09_part_2(&day_09_parse(EXAMPLE_SMALL).unwrap()), 132);
+        assert_eq!(day_09_part_2(&day_09_parse(EXAMPLE_BIG).unwrap()), 2858);
         // test cases found on r/adventofcode
-        assert_eq!(day_09_part_2("14113"), 16); // works
-        assert_eq!(day_09_part_2("1010101010101010101010"), 385); // works
-        assert_eq!(day_09_part_2("354631466260"), 1325); // works
-        assert_eq!(day_09_part_2("252"), 5); // works
-        assert_eq!(day_09_part_2("171010402"), 88); // works
-        assert_eq!(day_09_part_2("597689906"), 1840); // dosen't work \o/
+        assert_eq!(day_09_part_2(&day_09_parse("14113").unwrap()), 16); // works
+        assert_eq!(
+            day_09_part_2(&day_09_parse("1010101010101010101010").unwrap()),
+            385
+        ); // works
+        assert_eq!(day_09_part_2(&day_09_parse("354631466260").unwrap()), 1325); // works
+        assert_eq!(day_09_part_2(&day_09_parse("252").unwrap()), 5); // works
+        assert_eq!(day_09_part_2(&day_09_parse("171010402").unwrap()), 88); // works
+        assert_eq!(day_09_part_2(&day_09_parse("597689906").unwrap()), 1840); // dosen't work \o/
     }
 }
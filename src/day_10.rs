@@ -11,11 +11,13 @@
 */
 
 use ndarray::Array2;
-use nom::{
-    character::complete::{line_ending, one_of},
-    combinator::map,
-    multi::{many1, separated_list1},
-    IResult,
+use nom::{character::complete::one_of, combinator::map, IResult};
+
+use crate::{
+    answer::Answer,
+    grid::{neighbors_4, parse_grid},
+    parsing::{parse_all, ParseError},
+    solution::{Example, Solution, SolveError},
 };
 
 fn ascii_to_height(ascii: char) -> Option<u8> {
@@ -27,25 +29,10 @@ fn ascii_to_height(ascii: char) -> Option<u8> {
 }
 
 fn parse_input_data(data: &str) -> IResult<&str, Array2<Option<u8>>> {
-    map(
-        separated_list1(
-            line_ending,
-            many1(map(one_of("0123456789."), ascii_to_height)),
-        ),
-        |rows| {
-            let nb_rows = rows.len();
-            let nb_cols = rows.first().map_or(0, |row| row.len());
-
-            Array2::from_shape_fn((nb_rows, nb_cols), |(row, col)| rows[row][col])
-        },
-    )(data)
+    parse_grid(map(one_of("0123456789."), ascii_to_height))(data)
 }
 
-fn do_day(data: &str, skip_visited: bool) -> i64 {
-    let (_, grid) = parse_input_data(data).expect("Failed to parse input data");
-    let nrows = grid.nrows();
-    let ncols = grid.ncols();
-
+fn do_day(grid: &Array2<Option<u8>>, skip_visited: bool) -> i64 {
     let mut counters = Array2::<usize>::zeros(grid.dim());
     let mut visited =
         Array2::<bool>::from_elem(if skip_visited { grid.dim() } else { (0, 0) }, false);
@@ -72,17 +59,10 @@ fn do_day(data: &str, skip_visited: bool) -> i64 {
                 continue;
             }
             let target_height = height - 1;
-            if row > 0 && grid[(row - 1, col)] == Some(target_height) {
-                dfs_pile.push(((row - 1, col), target_height));
-            }
-            if row < nrows - 1 && grid[(row + 1, col)] == Some(target_height) {
-                dfs_pile.push(((row + 1, col), target_height));
-            }
-            if col > 0 && grid[(row, col - 1)] == Some(target_height) {
-                dfs_pile.push(((row, col - 1), target_height));
-            }
-            if col < ncols - 1 && grid[(row, col + 1)] == Some(target_height) {
-                dfs_pile.push(((row, col + 1), target_height));
+            for neighbor in neighbors_4(grid.dim(), (row, col)) {
+                if grid[neighbor] == Some(target_height) {
+                    dfs_pile.push((neighbor, target_height));
+                }
             }
         }
     }
@@ -99,12 +79,60 @@ fn do_day(data: &str, skip_visited: bool) -> i64 {
         .sum::<usize>() as i64
 }
 
-pub fn day_10_part_1(data: &str) -> i64 {
-    do_day(data, true)
+pub fn day_10_parse(data: &str) -> Result<Array2<Option<u8>>, ParseError> {
+    parse_all(10, data, parse_input_data)
+}
+
+pub fn day_10_part_1(grid: &Array2<Option<u8>>) -> i64 {
+    do_day(grid, true)
+}
+
+pub fn day_10_part_2(grid: &Array2<Option<u8>>) -> i64 {
+    do_day(grid, false)
 }
 
-pub fn day_10_part_2(data: &str) -> i64 {
-    do_day(data, false)
+const EXAMPLE_E: &str = "89010123
+78121874
+87430965
+96549874
+45678903
+32019012
+01329801
+10456732";
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Input = Array2<Option<u8>>;
+
+    fn day(&self) -> u8 {
+        10
+    }
+
+    fn name(&self) -> &'static str {
+        "Hoof It"
+    }
+
+    fn parse(&self, data: &str) -> Result<Self::Input, ParseError> {
+        day_10_parse(data)
+    }
+
+    fn part_1(&self, input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(day_10_part_1(input).into())
+    }
+
+    fn part_2(&self, input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(day_10_part_2(input).into())
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            data: EXAMPLE_E,
+            part_1: Some("36"),
+            part_2: Some("81"),
+            params: &[],
+        }]
+    }
 }
 
 #[cfg(test)]
@@ -136,14 +164,6 @@ mod tests {
 ...8..3
 ...9..2
 .....01";
-    const EXAMPLE_E: &str = "89010123
-78121874
-87430965
-96549874
-45678903
-32019012
-01329801
-10456732";
 
     const EXAMPLE_F: &str = ".....0.
 ..4321.
@@ -170,18 +190,18 @@ mod tests {
 
     #[test]
     fn test_day_10_part_1() {
-        assert_eq!(day_10_part_1(EXAMPLE_A), 1);
-        assert_eq!(day_10_part_1(EXAMPLE_B), 2);
-        assert_eq!(day_10_part_1(EXAMPLE_C), 4);
-        assert_eq!(day_10_part_1(EXAMPLE_D), 3);
-        assert_eq!(day_10_part_1(EXAMPLE_E), 36);
+        assert_eq!(day_10_part_1(&day_10_parse(EXAMPLE_A).unwrap()), 1);
+        assert_eq!(day_10_part_1(&day_10_parse(EXAMPLE_B).unwrap()), 2);
+        assert_eq!(day_10_part_1(&day_10_parse(EXAMPLE_C).unwrap()), 4);
+        assert_eq!(day_10_part_1(&day_10_parse(EXAMPLE_D).unwrap()), 3);
+        assert_eq!(day_10_part_1(&day_10_parse(EXAMPLE_E).unwrap()), 36);
     }
 
     #[test]
     fn test_day_10_part_2() {
-        assert_eq!(day_10_part_2(EXAMPLE_F), 3);
-        assert_eq!(day_10_part_2(EXAMPLE_G), 13);
-        assert_eq!(day_10_part_2(EXAMPLE_H), 227);
-        assert_eq!(day_10_part_2(EXAMPLE_E), 81);
+        assert_eq!(day_10_part_2(&day_10_parse(EXAMPLE_F).unwrap()), 3);
+        assert_eq!(day_10_part_2(&day_10_parse(EXAMPLE_G).unwrap()), 13);
+        assert_eq!(day_10_part_2(&day_10_parse(EXAMPLE_H).unwrap()), 227);
+        assert_eq!(day_10_part_2(&day_10_parse(EXAMPLE_E).unwrap()), 81);
     }
 }
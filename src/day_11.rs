@@ -10,6 +10,12 @@
 use cached::proc_macro::cached;
 use nom::{character::complete::space1, multi::separated_list1, IResult};
 
+use crate::{
+    answer::Answer,
+    parsing::{parse_all, ParseError},
+    solution::{Example, Solution, SolveError},
+};
+
 fn parse_input_data(data: &str) -> IResult<&str, Vec<u64>> {
     separated_list1(space1, nom::character::complete::u64)(data)
 }
@@ -24,7 +30,7 @@ fn nb_digits(number: u64) -> u32 {
 
 fn split_in_two_per_digit(number: u64) -> (u64, u64) {
     let nb_digits = nb_digits(number);
-    if nb_digits % 2 != 0 {
+    if !nb_digits.is_multiple_of(2) {
         panic!("Number of digits is not even");
     }
     let mask = 10u64.pow(nb_digits / 2);
@@ -66,23 +72,59 @@ fn blink_v2(number: u64, generation_left: usize) -> u64 {
     blink_v2(number * 2024, next_generation_left)
 }
 
-pub fn day_11_part_1(data: &str) -> i64 {
-    let (_, data) = parse_input_data(data).expect("Failed to parse input data");
+pub fn day_11_parse(data: &str) -> Result<Vec<u64>, ParseError> {
+    parse_all(11, data, parse_input_data)
+}
 
+pub fn day_11_part_1(data: &[u64]) -> i64 {
     data.iter().map(|&n| blink_v2(n, 25)).sum::<u64>() as i64
 }
 
-pub fn day_11_part_2(data: &str) -> i64 {
-    let (_, data) = parse_input_data(data).expect("Failed to parse input data");
+pub fn day_11_part_2(data: &[u64]) -> i64 {
     data.iter().map(|&n| blink_v2(n, 75)).sum::<u64>() as i64
 }
 
+const EXAMPLE: &str = "125 17";
+
+pub struct Day11;
+
+impl Solution for Day11 {
+    type Input = Vec<u64>;
+
+    fn day(&self) -> u8 {
+        11
+    }
+
+    fn name(&self) -> &'static str {
+        "Plutonian Pebbles"
+    }
+
+    fn parse(&self, data: &str) -> Result<Self::Input, ParseError> {
+        day_11_parse(data)
+    }
+
+    fn part_1(&self, input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(day_11_part_1(input).into())
+    }
+
+    fn part_2(&self, input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(day_11_part_2(input).into())
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            data: EXAMPLE,
+            part_1: Some("55312"),
+            part_2: Some("65601038650482"),
+            params: &[],
+        }]
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const EXAMPLE: &str = "125 17";
-
     #[test]
     fn test_split_in_two_per_digit() {
         assert_eq!(split_in_two_per_digit(10), (1, 0));
@@ -102,11 +144,14 @@ mod tests {
 
     #[test]
     fn test_day_11_part_1() {
-        assert_eq!(day_11_part_1(EXAMPLE), 55312);
+        assert_eq!(day_11_part_1(&day_11_parse(EXAMPLE).unwrap()), 55312);
     }
 
     #[test]
     fn test_day_11_part_2() {
-        assert_eq!(day_11_part_2(EXAMPLE), 65601038650482);
+        assert_eq!(
+            day_11_part_2(&day_11_parse(EXAMPLE).unwrap()),
+            65601038650482
+        );
     }
 }
@@ -18,23 +18,17 @@
 use std::collections::HashSet;
 
 use ndarray::Array2;
-use nom::{
-    character::complete::{line_ending, one_of},
-    combinator::map,
-    multi::{many1, separated_list1},
-    IResult,
+use nom::IResult;
+
+use crate::{
+    answer::Answer,
+    grid::{offset_position, parse_char_grid},
+    parsing::{parse_all, ParseError},
+    solution::{Example, Solution, SolveError},
 };
 
 fn parse_input_data(data: &str) -> IResult<&str, Array2<char>> {
-    map(
-        separated_list1(line_ending, many1(one_of("ABCDEFGHIJKLMNOPQRSTUVWXYZ"))),
-        |rows| {
-            let nb_rows = rows.len();
-            let nb_cols = rows.first().map_or(0, |row| row.len());
-
-            Array2::from_shape_fn((nb_rows, nb_cols), |(row, col)| rows[row][col])
-        },
-    )(data)
+    parse_char_grid("ABCDEFGHIJKLMNOPQRSTUVWXYZ")(data)
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -45,11 +39,7 @@ enum Side {
     Left,
 }
 
-fn day_12(data: &str, part_two: bool) -> i64 {
-    let (_, grid) = parse_input_data(data).expect("Failed to parse input data");
-    let nrows = grid.nrows();
-    let ncols = grid.ncols();
-
+fn day_12(grid: &Array2<char>, part_two: bool) -> i64 {
     let mut visited = Array2::<bool>::from_elem(grid.dim(), false);
     let mut total_price: usize = 0;
 
@@ -72,49 +62,19 @@ fn day_12(data: &str, part_two: bool) -> i64 {
 
             area += 1;
 
-            if row > 0 {
-                if grid[(row - 1, col)] != region {
-                    //borders += 1;
-                    borders_set.insert((row, col, Side::Top));
-                } else {
-                    dfs_pile.push((row - 1, col));
-                }
-            } else {
-                //borders += 1;
-                borders_set.insert((row, col, Side::Top));
-            }
-            if row < nrows - 1 {
-                if grid[(row + 1, col)] != region {
-                    //borders += 1;
-                    borders_set.insert((row, col, Side::Bottom));
-                } else {
-                    dfs_pile.push((row + 1, col));
-                }
-            } else {
-                //borders += 1;
-                borders_set.insert((row, col, Side::Bottom));
-            }
-            if col > 0 {
-                if grid[(row, col - 1)] != region {
-                    //borders += 1;
-                    borders_set.insert((row, col, Side::Left));
-                } else {
-                    dfs_pile.push((row, col - 1));
+            // The edge of the map is a border too
+            for (side, offset) in [
+                (Side::Top, (-1, 0)),
+                (Side::Bottom, (1, 0)),
+                (Side::Left, (0, -1)),
+                (Side::Right, (0, 1)),
+            ] {
+                match offset_position(grid.dim(), (row, col), offset) {
+                    Some(neighbor) if grid[neighbor] == region => dfs_pile.push(neighbor),
+                    _ => {
+                        borders_set.insert((row, col, side));
+                    }
                 }
-            } else {
-                //borders += 1;
-                borders_set.insert((row, col, Side::Left));
-            }
-            if col < ncols - 1 {
-                if grid[(row, col + 1)] != region {
-                    //borders += 1;
-                    borders_set.insert((row, col, Side::Right));
-                } else {
-                    dfs_pile.push((row, col + 1));
-                }
-            } else {
-                //borders += 1;
-                borders_set.insert((row, col, Side::Right));
             }
         }
 
@@ -150,12 +110,62 @@ fn day_12(data: &str, part_two: bool) -> i64 {
     total_price as i64
 }
 
-pub fn day_12_part_1(data: &str) -> i64 {
-    day_12(data, false)
+pub fn day_12_parse(data: &str) -> Result<Array2<char>, ParseError> {
+    parse_all(12, data, parse_input_data)
+}
+
+pub fn day_12_part_1(grid: &Array2<char>) -> i64 {
+    day_12(grid, false)
 }
 
-pub fn day_12_part_2(data: &str) -> i64 {
-    day_12(data, true)
+pub fn day_12_part_2(grid: &Array2<char>) -> i64 {
+    day_12(grid, true)
+}
+
+const EXAMPLE_C: &str = "RRRRIICCFF
+RRRRIICCCF
+VVRRRCCFFF
+VVRCCCJFFF
+VVVVCJJCFE
+VVIVCCJJEE
+VVIIICJJEE
+MIIIIIJJEE
+MIIISIJEEE
+MMMISSJEEE";
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    type Input = Array2<char>;
+
+    fn day(&self) -> u8 {
+        12
+    }
+
+    fn name(&self) -> &'static str {
+        "Garden Groups"
+    }
+
+    fn parse(&self, data: &str) -> Result<Self::Input, ParseError> {
+        day_12_parse(data)
+    }
+
+    fn part_1(&self, input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(day_12_part_1(input).into())
+    }
+
+    fn part_2(&self, input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(day_12_part_2(input).into())
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            data: EXAMPLE_C,
+            part_1: Some("1930"),
+            part_2: Some("1206"),
+            params: &[],
+        }]
+    }
 }
 
 #[cfg(test)]
@@ -173,17 +183,6 @@ OOOOO
 OXOXO
 OOOOO";
 
-    const EXAMPLE_C: &str = "RRRRIICCFF
-RRRRIICCCF
-VVRRRCCFFF
-VVRCCCJFFF
-VVVVCJJCFE
-VVIVCCJJEE
-VVIIICJJEE
-MIIIIIJJEE
-MIIISIJEEE
-MMMISSJEEE";
-
     const EXAMPLE_D: &str = "EEEEE
 EXXXX
 EEEEE
@@ -199,17 +198,17 @@ AAAAAA";
 
     #[test]
     fn test_day_12_part_1() {
-        assert_eq!(day_12_part_1(EXAMPLE_A), 140);
-        assert_eq!(day_12_part_1(EXAMPLE_B), 772);
-        assert_eq!(day_12_part_1(EXAMPLE_C), 1930);
+        assert_eq!(day_12_part_1(&day_12_parse(EXAMPLE_A).unwrap()), 140);
+        assert_eq!(day_12_part_1(&day_12_parse(EXAMPLE_B).unwrap()), 772);
+        assert_eq!(day_12_part_1(&day_12_parse(EXAMPLE_C).unwrap()), 1930);
     }
 
     #[test]
     fn test_day_12_part_2() {
-        assert_eq!(day_12_part_2(EXAMPLE_A), 80);
-        assert_eq!(day_12_part_2(EXAMPLE_B), 436);
-        assert_eq!(day_12_part_2(EXAMPLE_C), 1206);
-        assert_eq!(day_12_part_2(EXAMPLE_D), 236);
-        assert_eq!(day_12_part_2(EXAMPLE_E), 368);
+        assert_eq!(day_12_part_2(&day_12_parse(EXAMPLE_A).unwrap()), 80);
+        assert_eq!(day_12_part_2(&day_12_parse(EXAMPLE_B).unwrap()), 436);
+        assert_eq!(day_12_part_2(&day_12_parse(EXAMPLE_C).unwrap()), 1206);
+        assert_eq!(day_12_part_2(&day_12_parse(EXAMPLE_D).unwrap()), 236);
+        assert_eq!(day_12_part_2(&day_12_parse(EXAMPLE_E).unwrap()), 368);
     }
 }
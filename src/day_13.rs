@@ -11,6 +11,12 @@ use nom::{
     multi::separated_list1, sequence::tuple, IResult,
 };
 
+use crate::{
+    answer::Answer,
+    parsing::{parse_all, ParseError},
+    solution::{Example, Solution, SolveError},
+};
+
 #[derive(Debug, Clone)]
 struct Button {
     x: i64,
@@ -24,7 +30,7 @@ struct Prize {
 }
 
 #[derive(Debug)]
-struct PrizeProblem {
+pub struct PrizeProblem {
     button_a: Button,
     button_b: Button,
     prize: Prize,
@@ -127,18 +133,18 @@ fn parse_input_data(data: &str) -> IResult<&str, Vec<PrizeProblem>> {
     separated_list1(tuple((line_ending, line_ending)), parse_prize_problem)(data)
 }
 
-pub fn day_13_part_1(data: &str) -> i64 {
-    let (_, data) = parse_input_data(data).expect("Failed to parse input data");
+pub fn day_13_parse(data: &str) -> Result<Vec<PrizeProblem>, ParseError> {
+    parse_all(13, data, parse_input_data)
+}
 
+pub fn day_13_part_1(data: &[PrizeProblem]) -> i64 {
     data.iter()
         .filter_map(|problem| problem.clicks_per_button())
         .map(|(clicks_a, clicks_b)| 3 * clicks_a + clicks_b)
         .sum()
 }
 
-pub fn day_13_part_2(data: &str) -> i64 {
-    let (_, data) = parse_input_data(data).expect("Failed to parse input data");
-
+pub fn day_13_part_2(data: &[PrizeProblem]) -> i64 {
     data.iter()
         .map(|problem| problem.to_part_two())
         .filter_map(|problem| problem.clicks_per_button())
@@ -146,11 +152,7 @@ pub fn day_13_part_2(data: &str) -> i64 {
         .sum()
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const EXAMPLE: &str = "Button A: X+94, Y+34
+const EXAMPLE: &str = "Button A: X+94, Y+34
 Button B: X+22, Y+67
 Prize: X=8400, Y=5400
 
@@ -166,13 +168,52 @@ Button A: X+69, Y+23
 Button B: X+27, Y+71
 Prize: X=18641, Y=10279";
 
+pub struct Day13;
+
+impl Solution for Day13 {
+    type Input = Vec<PrizeProblem>;
+
+    fn day(&self) -> u8 {
+        13
+    }
+
+    fn name(&self) -> &'static str {
+        "Claw Contraption"
+    }
+
+    fn parse(&self, data: &str) -> Result<Self::Input, ParseError> {
+        day_13_parse(data)
+    }
+
+    fn part_1(&self, input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(day_13_part_1(input).into())
+    }
+
+    fn part_2(&self, input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(day_13_part_2(input).into())
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            data: EXAMPLE,
+            part_1: Some("480"),
+            part_2: Some("875318608908"),
+            params: &[],
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     #[test]
     fn test_day_13_part_1() {
-        assert_eq!(day_13_part_1(EXAMPLE), 480);
+        assert_eq!(day_13_part_1(&day_13_parse(EXAMPLE).unwrap()), 480);
     }
 
     #[test]
     fn test_day_13_part_2() {
-        assert_eq!(day_13_part_2(EXAMPLE), 875318608908);
+        assert_eq!(day_13_part_2(&day_13_parse(EXAMPLE).unwrap()), 875318608908);
     }
 }
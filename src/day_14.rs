@@ -31,35 +31,64 @@
     The probability to have it between 1 and 10000 times in a row
     is about 97%. Good enough.
 
+    The room and the seconds of part 1 are parameters now, the puzzle
+    values by default, and the example runs in its 11x7 room instead of
+    guessing the room from the number of robots.
+
+    The 4% bothered me in the end. The tree is a lot of robots packed
+    together, so the columns have their lowest variance at the tree time
+    modulo 101, and the rows at the tree time modulo 103. It only takes
+    101 + 103 frames, and the Chinese remainder theorem gives the time.
+    It's the default detector now, the no overlap one is still there,
+    and `robots --around-tree 0 --output -` prints the frame to check
+    that it's a tree.
 */
 
+use std::{
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
 use ndarray::Array2;
 use nom::{
     bytes::complete::tag, character::complete::line_ending, combinator::map,
     multi::separated_list1, sequence::tuple, IResult,
 };
 
-#[derive(Debug)]
+use crate::{
+    answer::Answer,
+    grid::{render_with, Size},
+    params::{Param, Params},
+    parsing::{parse_all, ParseError},
+    solution::{Example, Solution, SolveError},
+};
+
+#[derive(Debug, Clone)]
 struct Velocity {
     col: i64,
     row: i64,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct Position {
     col: i64,
     row: i64,
 }
 
-#[derive(Debug)]
-struct Robot {
+#[derive(Debug, Clone)]
+pub struct Robot {
     position: Position,
     velocity: Velocity,
 }
 
 fn move_robot(robot: &Robot, time_span: i64, wide: i64, tall: i64) -> Position {
-    let col = (((robot.position.col + robot.velocity.col * time_span) % wide) + wide) % wide;
-    let row = (((robot.position.row + robot.velocity.row * time_span) % tall) + tall) % tall;
+    // The room wraps around, so only the time modulo its size matters,
+    // and long time spans don't overflow.
+    let col_span = time_span % wide;
+    let row_span = time_span % tall;
+    let col = (((robot.position.col + robot.velocity.col * col_span) % wide) + wide) % wide;
+    let row = (((robot.position.row + robot.velocity.row * row_span) % tall) + tall) % tall;
     Position { col, row }
 }
 
@@ -106,17 +135,52 @@ fn parse_input_data(data: &str) -> IResult<&str, Vec<Robot>> {
     separated_list1(line_ending, parse_robot)(data)
 }
 
-pub fn day_14_part_1(data: &str) -> i64 {
-    let (_, data) = parse_input_data(data).expect("Failed to parse input data");
+pub fn day_14_parse(data: &str) -> Result<Vec<Robot>, ParseError> {
+    parse_all(14, data, parse_input_data)
+}
 
-    // Different area size between the example and the actual input
-    let (wide, tall) = if data.len() > 100 {
-        (101, 103)
-    } else {
-        (11, 7)
-    };
+/// The room of the robots, and how long they move in part 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Room {
+    pub size: Size,
+    pub seconds: i64,
+}
 
-    let time_span = 100;
+impl Default for Room {
+    fn default() -> Self {
+        Room {
+            size: Size {
+                width: 101,
+                height: 103,
+            },
+            seconds: 100,
+        }
+    }
+}
+
+impl Room {
+    /// The puzzle room, with the parameters given on top of it.
+    pub fn from_params(params: &Params) -> Result<Room, SolveError> {
+        let room = Room::default();
+        Ok(Room {
+            size: params.get("size")?.unwrap_or(room.size),
+            seconds: params.get("seconds")?.unwrap_or(room.seconds),
+        })
+    }
+
+    fn wide(&self) -> i64 {
+        self.size.width as i64
+    }
+
+    fn tall(&self) -> i64 {
+        self.size.height as i64
+    }
+}
+
+pub fn day_14_part_1(data: &[Robot], room: Room) -> i64 {
+    let (wide, tall) = (room.wide(), room.tall());
+
+    let time_span = room.seconds;
     let col_split = wide / 2;
     let row_split = tall / 2;
 
@@ -148,16 +212,19 @@ pub fn day_14_part_1(data: &str) -> i64 {
     counter_top_left * counter_top_right * counter_bottom_left * counter_bottom_right
 }
 
-pub fn day_14_part_2(data: &str) -> i64 {
-    let (_, mut data) = parse_input_data(data).expect("Failed to parse input data");
-    let wide = 101;
-    let tall = 103;
+/// The first time when no robots overlap, the first detector. It works
+/// on most inputs, not all of them.
+pub fn first_time_without_overlap(data: &[Robot], room: Room) -> Result<i64, SolveError> {
+    let mut data = data.to_vec();
+    let (wide, tall) = (room.wide(), room.tall());
+
+    // The robots are back where they started after wide * tall seconds
+    // at most, if they never stop overlapping there is no tree.
+    let period = wide * tall;
 
     // assumption, no overlapping robots
-    let mut time = 0;
     let mut positions = Array2::<bool>::from_elem((wide as usize, tall as usize), false);
-    loop {
-        time += 1;
+    for time in 1..=period {
         for robot in data.iter_mut() {
             robot.update_position(1, wide, tall);
         }
@@ -174,17 +241,221 @@ pub fn day_14_part_2(data: &str) -> i64 {
             }
         }
         if !found_overlap {
-            break;
+            return Ok(time);
         }
     }
-    time
+    Err(SolveError::NoSolution)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// The time, modulo `period`, when the robots are the least spread out on
+/// an axis, given as (position, velocity) along that axis.
+pub fn least_spread_time(axis: &[(i64, i64)], period: i64) -> i64 {
+    let count = axis.len() as i64;
+    (0..period)
+        .min_by_key(|&time| {
+            let (sum, sum_squares) = axis.iter().fold((0, 0), |(sum, sum_squares), &(p, v)| {
+                let x = (p + v * time).rem_euclid(period);
+                (sum + x, sum_squares + x * x)
+            });
+            // the variance, times count², to stay with integers
+            count * sum_squares - sum * sum
+        })
+        .expect("The room is not empty")
+}
+
+/// The inverse of `a` modulo `modulo`, if they are coprime.
+fn modular_inverse(a: i64, modulo: i64) -> Option<i64> {
+    // extended Euclid
+    let (mut old_r, mut r) = (a.rem_euclid(modulo), modulo);
+    let (mut old_s, mut s) = (1, 0);
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_s, s) = (s, old_s - quotient * s);
+    }
+    (old_r == 1).then(|| old_s.rem_euclid(modulo))
+}
+
+/// The tree is where the robots are packed together, so on each axis it's
+/// the time with the lowest variance. The columns repeat every `wide`
+/// seconds and the rows every `tall` seconds, the Chinese remainder theorem
+/// gives the time when both are at their lowest.
+pub fn tree_time_by_variance(data: &[Robot], room: Room) -> Result<i64, SolveError> {
+    let (wide, tall) = (room.wide(), room.tall());
+    if data.is_empty() {
+        return Err(SolveError::NoSolution);
+    }
+    let inverse = modular_inverse(wide, tall).ok_or_else(|| {
+        SolveError::Unsupported(format!(
+            "the room is {}, its width and height must be coprime to combine the axes",
+            room.size
+        ))
+    })?;
+
+    let cols = data
+        .iter()
+        .map(|robot| (robot.position.col, robot.velocity.col))
+        .collect::<Vec<_>>();
+    let rows = data
+        .iter()
+        .map(|robot| (robot.position.row, robot.velocity.row))
+        .collect::<Vec<_>>();
+    let col_time = least_spread_time(&cols, wide);
+    let row_time = least_spread_time(&rows, tall);
+
+    // time = col_time + wide * k, and time = row_time modulo tall
+    let k = ((row_time - col_time) * inverse).rem_euclid(tall);
+    Ok(col_time + wide * k)
+}
+
+/// How part 2 recognises the Christmas tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TreeDetector {
+    /// The lowest variance on both axes, combined with the CRT.
+    #[default]
+    Variance,
+    /// The first time without overlapping robots.
+    NoOverlap,
+}
+
+impl FromStr for TreeDetector {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "variance" => Ok(TreeDetector::Variance),
+            "no-overlap" => Ok(TreeDetector::NoOverlap),
+            _ => Err("expected variance or no-overlap".to_string()),
+        }
+    }
+}
+
+pub fn day_14_part_2(
+    data: &[Robot],
+    room: Room,
+    detector: TreeDetector,
+) -> Result<i64, SolveError> {
+    match detector {
+        TreeDetector::Variance => tree_time_by_variance(data, room),
+        TreeDetector::NoOverlap => first_time_without_overlap(data, room),
+    }
+}
+
+/// How many robots are on each cell of the room after `time` seconds,
+/// indexed by (row, col).
+pub fn robot_counts(data: &[Robot], size: Size, time: i64) -> Array2<usize> {
+    let (wide, tall) = (size.width as i64, size.height as i64);
+    let mut room = Array2::<usize>::zeros((size.height, size.width));
+    for robot in data {
+        let Position { col, row } = move_robot(robot, time, wide, tall);
+        room[[row as usize, col as usize]] += 1;
+    }
+    room
+}
+
+/// The robots in the room after `time` seconds, `#` where there is at
+/// least one robot.
+pub fn render_robots(data: &[Robot], size: Size, time: i64) -> String {
+    render_frame(data, size, time, FrameFormat::Ascii)
+}
+
+/// The formats of the exported frames.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum FrameFormat {
+    /// The room as text, `#` where there are robots.
+    Ascii,
+    /// A black and white plain PBM image, black where there are robots.
+    Pbm,
+    /// A greyscale plain PGM image, the more robots the brighter.
+    Pgm,
+}
+
+impl FrameFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            FrameFormat::Ascii => "txt",
+            FrameFormat::Pbm => "pbm",
+            FrameFormat::Pgm => "pgm",
+        }
+    }
+}
+
+/// Writes a row of pixels, the plain netpbm formats want lines of 70
+/// characters at most so a long row is split.
+fn push_pixels(frame: &mut String, pixels: impl Iterator<Item = String>) {
+    let mut line_length = 0;
+    for pixel in pixels {
+        if line_length > 0 && line_length + 1 + pixel.len() > 70 {
+            frame.push('\n');
+            line_length = 0;
+        } else if line_length > 0 {
+            frame.push(' ');
+            line_length += 1;
+        }
+        frame.push_str(&pixel);
+        line_length += pixel.len();
+    }
+    frame.push('\n');
+}
+
+/// A frame of the robots after `time` seconds, in the given format. The
+/// images are the plain (ASCII) variants of the netpbm formats, any image
+/// viewer opens them and they don't need an image crate.
+pub fn render_frame(data: &[Robot], size: Size, time: i64, format: FrameFormat) -> String {
+    let room = robot_counts(data, size, time);
+    let mut frame = String::new();
+    match format {
+        FrameFormat::Ascii => {
+            let text = render_with(&room, |_, &count| if count > 0 { '#' } else { '.' });
+            frame.push_str(&text);
+        }
+        FrameFormat::Pbm => {
+            frame.push_str(&format!(
+                "P1\n# {} seconds\n{} {}\n",
+                time, size.width, size.height
+            ));
+            for line in room.outer_iter() {
+                push_pixels(
+                    &mut frame,
+                    line.iter()
+                        .map(|&count| if count > 0 { "1" } else { "0" }.to_string()),
+                );
+            }
+        }
+        FrameFormat::Pgm => {
+            let max_count = room.iter().copied().max().unwrap_or(0).max(1);
+            frame.push_str(&format!(
+                "P2\n# {} seconds\n{} {}\n{}\n",
+                time, size.width, size.height, max_count
+            ));
+            for line in room.outer_iter() {
+                push_pixels(&mut frame, line.iter().map(|count| count.to_string()));
+            }
+        }
+    }
+    frame
+}
 
-    const EXAMPLE: &str = "p=0,4 v=3,-3
+/// Writes a frame per second of `times` in `directory`, named after their
+/// time like `frame_006543.pgm`, and returns the paths of the files.
+pub fn export_frames(
+    data: &[Robot],
+    size: Size,
+    times: RangeInclusive<i64>,
+    format: FrameFormat,
+    directory: &Path,
+) -> std::io::Result<Vec<PathBuf>> {
+    std::fs::create_dir_all(directory)?;
+    times
+        .map(|time| {
+            let path = directory.join(format!("frame_{:06}.{}", time, format.extension()));
+            std::fs::write(&path, render_frame(data, size, time, format))?;
+            Ok(path)
+        })
+        .collect()
+}
+
+const EXAMPLE: &str = "p=0,4 v=3,-3
 p=6,3 v=-1,-3
 p=10,3 v=-1,2
 p=2,0 v=2,-1
@@ -197,13 +468,240 @@ p=7,3 v=-1,2
 p=2,4 v=2,-3
 p=9,5 v=-3,-3";
 
+pub struct Day14;
+
+impl Solution for Day14 {
+    type Input = Vec<Robot>;
+
+    fn day(&self) -> u8 {
+        14
+    }
+
+    fn name(&self) -> &'static str {
+        "Restroom Redoubt"
+    }
+
+    fn parse(&self, data: &str) -> Result<Self::Input, ParseError> {
+        day_14_parse(data)
+    }
+
+    fn part_1(&self, input: &Self::Input) -> Result<Answer, SolveError> {
+        self.solve_with(1, input, &Params::default())
+    }
+
+    fn part_2(&self, input: &Self::Input) -> Result<Answer, SolveError> {
+        self.solve_with(2, input, &Params::default())
+    }
+
+    fn params(&self) -> &'static [Param] {
+        &[
+            Param {
+                name: "size",
+                help: "room as WIDTHxHEIGHT, 101x103 by default",
+            },
+            Param {
+                name: "seconds",
+                help: "seconds of part 1, 100 by default",
+            },
+            Param {
+                name: "detector",
+                help: "tree of part 2 by variance or no-overlap",
+            },
+        ]
+    }
+
+    fn solve_with(
+        &self,
+        part: u8,
+        input: &Self::Input,
+        params: &Params,
+    ) -> Result<Answer, SolveError> {
+        let room = Room::from_params(params)?;
+        match part {
+            1 => Ok(day_14_part_1(input, room).into()),
+            _ => {
+                let detector = params.get("detector")?.unwrap_or_default();
+                Ok(day_14_part_2(input, room, detector)?.into())
+            }
+        }
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            data: EXAMPLE,
+            part_1: Some("12"),
+            part_2: None,
+            params: &[("size", "11x7")],
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE_ROOM: Room = Room {
+        size: Size {
+            width: 11,
+            height: 7,
+        },
+        seconds: 100,
+    };
+
     #[test]
     fn test_day_14_part_1() {
-        assert_eq!(day_14_part_1(EXAMPLE), 12);
+        assert_eq!(
+            day_14_part_1(&day_14_parse(EXAMPLE).unwrap(), EXAMPLE_ROOM),
+            12
+        );
+    }
+
+    /// Robots that draw a filled triangle at `time`, with a few robots
+    /// around it, all of them moving at pseudo-random velocities.
+    fn tree_robots(room: Room, time: i64) -> Vec<Robot> {
+        let (wide, tall) = (room.wide(), room.tall());
+        let mut seed: i64 = 42;
+        let mut random = |modulo: i64| {
+            seed = (seed * 1_103_515_245 + 12_345) % 2_147_483_648;
+            seed % modulo
+        };
+        let mut positions = Vec::new();
+        for row in 0..8 {
+            for col in (8 - row)..(9 + row) {
+                positions.push((col + 5, row + 6));
+            }
+        }
+        for _ in 0..20 {
+            positions.push((random(wide), random(tall)));
+        }
+        positions
+            .into_iter()
+            .map(|(col, row)| {
+                let velocity = Velocity {
+                    col: random(wide),
+                    row: random(tall),
+                };
+                // back in time to where the robot starts
+                let robot = Robot {
+                    position: Position { col, row },
+                    velocity: velocity.clone(),
+                };
+                Robot {
+                    position: move_robot(&robot, -time, wide, tall),
+                    velocity,
+                }
+            })
+            .collect()
     }
 
     #[test]
     fn test_day_14_part_2() {
-        assert_eq!(day_14_part_2(EXAMPLE), 1);
+        let robots = day_14_parse(EXAMPLE).unwrap();
+        assert_eq!(
+            day_14_part_2(&robots, EXAMPLE_ROOM, TreeDetector::NoOverlap),
+            Ok(1)
+        );
+        let stacked = day_14_parse("p=0,0 v=1,1\np=0,0 v=1,1").unwrap();
+        assert_eq!(
+            first_time_without_overlap(&stacked, EXAMPLE_ROOM),
+            Err(SolveError::NoSolution)
+        );
+    }
+
+    #[test]
+    fn test_day_14_tree_by_variance() {
+        let room = Room {
+            size: Size {
+                width: 31,
+                height: 29,
+            },
+            seconds: 100,
+        };
+        let robots = tree_robots(room, 500);
+        assert_eq!(tree_time_by_variance(&robots, room), Ok(500));
+        // It's the detector of part 2 when none is given
+        let mut params = Params::default();
+        params.set("size", "31x29");
+        assert_eq!(
+            Day14.solve_with(2, &robots, &params),
+            Ok(Answer::from(500_i64))
+        );
+        let frame = render_robots(&robots, room.size, 500);
+        assert_eq!(frame.lines().count(), 29);
+        assert!(frame.contains("\n........###########............\n"));
+        assert!(frame.contains("\n......###############..........\n"));
+
+        let even = Room {
+            size: Size {
+                width: 10,
+                height: 20,
+            },
+            seconds: 100,
+        };
+        assert!(matches!(
+            tree_time_by_variance(&robots, even),
+            Err(SolveError::Unsupported(_))
+        ));
+    }
+
+    #[test]
+    fn test_day_14_frames() {
+        let robots = day_14_parse("p=0,0 v=1,0\np=2,1 v=0,0\np=2,1 v=1,1").unwrap();
+        let size = Size {
+            width: 3,
+            height: 2,
+        };
+        assert_eq!(
+            render_frame(&robots, size, 0, FrameFormat::Ascii),
+            "#..\n..#\n"
+        );
+        assert_eq!(
+            render_frame(&robots, size, 1, FrameFormat::Pbm),
+            "P1\n# 1 seconds\n3 2\n1 1 0\n0 0 1\n"
+        );
+        assert_eq!(
+            render_frame(&robots, size, 0, FrameFormat::Pgm),
+            "P2\n# 0 seconds\n3 2\n2\n1 0 0\n0 0 2\n"
+        );
+
+        let directory = std::env::temp_dir().join(format!("day_14_frames_{}", std::process::id()));
+        let paths = export_frames(&robots, size, 3..=4, FrameFormat::Pbm, &directory).unwrap();
+        assert_eq!(
+            paths,
+            vec![
+                directory.join("frame_000003.pbm"),
+                directory.join("frame_000004.pbm")
+            ]
+        );
+        assert_eq!(
+            std::fs::read_to_string(&paths[1]).unwrap(),
+            render_frame(&robots, size, 4, FrameFormat::Pbm)
+        );
+        std::fs::remove_dir_all(&directory).unwrap();
+
+        let wide = Size {
+            width: 101,
+            height: 1,
+        };
+        let frame = render_frame(&robots, wide, 0, FrameFormat::Pbm);
+        assert!(frame.lines().all(|line| line.len() <= 70));
+        assert_eq!(frame.split_whitespace().count(), 1 + 3 + 2 + 101);
+    }
+
+    #[test]
+    fn test_day_14_room() {
+        let mut params = Params::default();
+        assert_eq!(Room::from_params(&params), Ok(Room::default()));
+        params.set("size", "11x7");
+        params.set("seconds", "5");
+        let room = Room::from_params(&params).unwrap();
+        assert_eq!(room.seconds, 5);
+        assert_eq!(room.size, EXAMPLE_ROOM.size);
+        // After 5 seconds, the robot of the puzzle description is at 1,3
+        let robot = day_14_parse("p=2,4 v=2,-3").unwrap();
+        assert_eq!(day_14_part_1(&robot, room), 0);
+        let mut robot = robot[0].clone();
+        robot.update_position(5, room.wide(), room.tall());
+        assert_eq!((robot.position.col, robot.position.row), (1, 3));
     }
 }
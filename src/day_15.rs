@@ -8,8 +8,6 @@
 
 use ndarray::Array2;
 use nom::{
-    branch::alt,
-    bytes::complete::tag,
     character::complete::line_ending,
     combinator::map,
     multi::{many1, separated_list1},
@@ -17,16 +15,16 @@ use nom::{
     IResult,
 };
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
+use crate::{
+    answer::Answer,
+    direction::{parse_arrow, Direction},
+    grid::{find_cell, parse_cells, render_with, GridCell},
+    parsing::{parse_all, ParseError},
+    solution::{Example, Solution, SolveError},
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum Cell {
+pub enum Cell {
     Empty,
     Box,
     Wall,
@@ -36,67 +34,54 @@ enum Cell {
     BoxRight,
 }
 
-fn parse_direction(input: &str) -> IResult<&str, Direction> {
-    alt((
-        map(tag("^"), |_| Direction::Up),
-        map(tag("v"), |_| Direction::Down),
-        map(tag("<"), |_| Direction::Left),
-        map(tag(">"), |_| Direction::Right),
-    ))(input)
-}
-
 fn parse_directions(input: &str) -> IResult<&str, Vec<Direction>> {
     map(
-        separated_list1(line_ending, many1(parse_direction)),
+        separated_list1(line_ending, many1(parse_arrow)),
         |directions| directions.into_iter().flatten().collect(),
     )(input)
 }
 
-fn parse_cell(input: &str) -> IResult<&str, Cell> {
-    alt((
-        map(tag("#"), |_| Cell::Wall),
-        map(tag("O"), |_| Cell::Box),
-        map(tag("@"), |_| Cell::Robot),
-        map(tag("."), |_| Cell::Empty),
-    ))(input)
-}
-
-fn parse_map(input: &str) -> IResult<&str, Array2<Cell>> {
-    map(separated_list1(line_ending, many1(parse_cell)), |grid| {
-        let nb_rows = grid.len();
-        let nb_cols = grid.first().map_or(0, |row| row.len());
+impl GridCell for Cell {
+    fn from_char(c: char) -> Option<Self> {
+        match c {
+            '#' => Some(Cell::Wall),
+            'O' => Some(Cell::Box),
+            '@' => Some(Cell::Robot),
+            '.' => Some(Cell::Empty),
+            _ => None,
+        }
+    }
 
-        Array2::from_shape_fn((nb_rows, nb_cols), |(row, col)| grid[row][col])
-    })(input)
+    fn to_char(&self) -> char {
+        match self {
+            Cell::Empty => '.',
+            Cell::Box => 'O',
+            Cell::Wall => '#',
+            Cell::Robot => '@',
+            // Part 2
+            Cell::BoxLeft => '[',
+            Cell::BoxRight => ']',
+        }
+    }
 }
 
 fn parse_input_data(input: &str) -> IResult<&str, (Array2<Cell>, Vec<Direction>)> {
     map(
-        tuple((parse_map, line_ending, line_ending, parse_directions)),
+        tuple((parse_cells, line_ending, line_ending, parse_directions)),
         |(grid, _, _, directions)| (grid, directions),
     )(input)
 }
 
 #[allow(dead_code)]
 fn print_map(map: &Array2<Cell>, robot_position: (usize, usize)) {
-    for (row, line) in map.outer_iter().enumerate() {
-        for (col, cell) in line.iter().enumerate() {
-            if (row, col) == robot_position {
-                print!("@");
-            } else {
-                match cell {
-                    Cell::Empty => print!("."),
-                    Cell::Box => print!("O"),
-                    Cell::Wall => print!("#"),
-                    Cell::Robot => panic!("Robot cell should not be in the map"),
-                    // Part 2
-                    Cell::BoxLeft => print!("["),
-                    Cell::BoxRight => print!("]"),
-                }
-            }
-        }
-        println!();
-    }
+    print!(
+        "{}",
+        render_with(map, |position, cell| if position == robot_position {
+            '@'
+        } else {
+            cell.to_char()
+        })
+    );
 }
 
 // returns a boolean showing if the move has been successful and the new position
@@ -106,33 +91,8 @@ fn attempt_push(
     direction: Direction,
 ) -> (bool, (usize, usize)) {
     let (row, col) = position;
-    let ncols = map.ncols();
-    let nrows = map.nrows();
-    let (new_row, new_col) = match direction {
-        Direction::Up => {
-            if row == 0 {
-                return (false, (row, col));
-            }
-            (row - 1, col)
-        }
-        Direction::Down => {
-            if row == nrows - 1 {
-                return (false, (row, col));
-            }
-            (row + 1, col)
-        }
-        Direction::Left => {
-            if col == 0 {
-                return (false, (row, col));
-            }
-            (row, col - 1)
-        }
-        Direction::Right => {
-            if col == ncols - 1 {
-                return (false, (row, col));
-            }
-            (row, col + 1)
-        }
+    let Some((new_row, new_col)) = direction.step(map.dim(), position) else {
+        return (false, (row, col));
     };
 
     let cell = map[(new_row, new_col)];
@@ -288,43 +248,41 @@ fn push_down_rec(map: &mut Array2<Cell>, position: (usize, usize), visited: &mut
     map.swap((row, col), (row + 1, col));
 }
 
-fn extract_robot(map: &mut Array2<Cell>) -> (usize, usize) {
-    let robot_position = map
-        .indexed_iter()
-        .find_map(|(position, &cell)| {
-            if cell == Cell::Robot {
-                Some(position)
-            } else {
-                None
-            }
-        })
-        .expect("Robot not found");
+fn extract_robot(map: &mut Array2<Cell>) -> Result<(usize, usize), SolveError> {
+    let robot_position = find_cell(map, &Cell::Robot).ok_or(SolveError::MissingCell("robot"))?;
 
     map[robot_position] = Cell::Empty;
 
-    robot_position
+    Ok(robot_position)
+}
+
+pub fn day_15_parse(data: &str) -> Result<(Array2<Cell>, Vec<Direction>), ParseError> {
+    parse_all(15, data, parse_input_data)
 }
 
-pub fn day_15_part_1(data: &str) -> i64 {
-    let (_, (mut map, directions)) = parse_input_data(data).expect("Failed to parse input data");
+pub fn day_15_part_1(
+    (map, directions): &(Array2<Cell>, Vec<Direction>),
+) -> Result<i64, SolveError> {
+    let mut map = map.clone();
 
     // find the position of the robot
-    let mut robot_position = extract_robot(&mut map);
+    let mut robot_position = extract_robot(&mut map)?;
 
     //print_map(&map, robot_position);
 
     for direction in directions {
-        let (moved, new_position) = attempt_push(&mut map, robot_position, direction);
+        let (moved, new_position) = attempt_push(&mut map, robot_position, *direction);
         if moved {
             robot_position = new_position;
         }
         //print_map(&map, robot_position);
     }
 
-    map.indexed_iter()
+    Ok(map
+        .indexed_iter()
         .filter(|(_, &cell)| cell == Cell::Box)
         .map(|((row, col), _)| 100 * row + col)
-        .sum::<usize>() as i64
+        .sum::<usize>() as i64)
 }
 
 fn widen_map(map: &Array2<Cell>) -> Array2<Cell> {
@@ -349,44 +307,30 @@ fn widen_map(map: &Array2<Cell>) -> Array2<Cell> {
     })
 }
 
-pub fn day_15_part_2(data: &str) -> i64 {
-    let (_, (map, directions)) = parse_input_data(data).expect("Failed to parse input data");
-
-    let mut map = widen_map(&map);
-    let mut robot_position = extract_robot(&mut map);
+pub fn day_15_part_2(
+    (map, directions): &(Array2<Cell>, Vec<Direction>),
+) -> Result<i64, SolveError> {
+    let mut map = widen_map(map);
+    let mut robot_position = extract_robot(&mut map)?;
 
     //print_map(&map, robot_position);
     for direction in directions {
         //println!("{:?}", direction);
-        let (moved, new_position) = attempt_push(&mut map, robot_position, direction);
+        let (moved, new_position) = attempt_push(&mut map, robot_position, *direction);
         if moved {
             robot_position = new_position;
         }
         //print_map(&map, robot_position);
     }
 
-    map.indexed_iter()
+    Ok(map
+        .indexed_iter()
         .filter(|(_, &cell)| cell == Cell::BoxLeft)
         .map(|((row, col), _)| 100 * row + col)
-        .sum::<usize>() as i64
+        .sum::<usize>() as i64)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const SMALL_EXAMPLE: &str = "########
-#..O.O.#
-##@.O..#
-#...O..#
-#.#.O..#
-#...O..#
-#......#
-########
-
-<^^>>>vv<v>>v<<";
-
-    const BIG_EXAMPLE: &str = "##########
+const BIG_EXAMPLE: &str = "##########
 #..O..O.O#
 #......O.#
 #.OO..O.O#
@@ -408,6 +352,56 @@ vvv<<^>^v^^><<>>><>^<<><^vv^^<>vvv<>><^^v>^>vv<>v<<<<v<^v>^<^^>>>^<v<v
 ^^>vv<^v^v<vv>^<><v<^v>^^^>>>^^vvv^>vvv<>>>^<^>>>>>^<<^v>^vvv<>^<><<v>
 v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^";
 
+pub struct Day15;
+
+impl Solution for Day15 {
+    type Input = (Array2<Cell>, Vec<Direction>);
+
+    fn day(&self) -> u8 {
+        15
+    }
+
+    fn name(&self) -> &'static str {
+        "Warehouse Woes"
+    }
+
+    fn parse(&self, data: &str) -> Result<Self::Input, ParseError> {
+        day_15_parse(data)
+    }
+
+    fn part_1(&self, input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(day_15_part_1(input)?.into())
+    }
+
+    fn part_2(&self, input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(day_15_part_2(input)?.into())
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            data: BIG_EXAMPLE,
+            part_1: Some("10092"),
+            part_2: Some("9021"),
+            params: &[],
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SMALL_EXAMPLE: &str = "########
+#..O.O.#
+##@.O..#
+#...O..#
+#.#.O..#
+#...O..#
+#......#
+########
+
+<^^>>>vv<v>>v<<";
+
     const EXAMPLE_PART_TWO: &str = "#######
 #...#.#
 #.....#
@@ -420,13 +414,22 @@ v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^";
 
     #[test]
     fn test_day_15_part_1() {
-        assert_eq!(day_15_part_1(SMALL_EXAMPLE), 2028);
-        assert_eq!(day_15_part_1(BIG_EXAMPLE), 10092);
+        assert_eq!(
+            day_15_part_1(&day_15_parse(SMALL_EXAMPLE).unwrap()),
+            Ok(2028)
+        );
+        assert_eq!(
+            day_15_part_1(&day_15_parse(BIG_EXAMPLE).unwrap()),
+            Ok(10092)
+        );
     }
 
     #[test]
     fn test_day_15_part_2() {
-        assert_eq!(day_15_part_2(EXAMPLE_PART_TWO), 618);
-        assert_eq!(day_15_part_2(BIG_EXAMPLE), 9021);
+        assert_eq!(
+            day_15_part_2(&day_15_parse(EXAMPLE_PART_TWO).unwrap()),
+            Ok(618)
+        );
+        assert_eq!(day_15_part_2(&day_15_parse(BIG_EXAMPLE).unwrap()), Ok(9021));
     }
 }
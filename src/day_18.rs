@@ -1,170 +1,353 @@
 /*
     Part 1 is a straightforward A* pathfinding problem.
     Simpler version than day 16 part 1, worked first try.
+    It's now the A* of the pathfinding module.
 
     Part 2 is a binary search using part 1. Perhaps not the
     most efficient way to solve it, but it works.
-*/
 
-use std::{cmp::Reverse, collections::BinaryHeap};
+    It isn't, the time cube takes width × height × bytes booleans and
+    that hurts on bigger memory spaces. The other way is to let every byte
+    fall, then remove them from the last one with a disjoint-set of the
+    free cells, until the start and the exit are in the same set again.
+    `--param solver=union-find` picks it, so both can be compared.
+*/
 
 use ndarray::prelude::*;
 use ndarray::{Array2, Array3};
+use std::str::FromStr;
+
 use nom::{
-    bytes::complete::tag, character::complete::line_ending, multi::separated_list1,
-    sequence::separated_pair, IResult,
+    bytes::complete::tag,
+    character::complete::line_ending,
+    combinator::{map, opt, verify},
+    multi::separated_list1,
+    sequence::{preceded, separated_pair, terminated, tuple},
+    IResult,
 };
 
-fn parse_input_data(data: &str) -> IResult<&str, Vec<(u64, u64)>> {
-    separated_list1(
-        line_ending,
-        separated_pair(
-            nom::character::complete::u64,
-            tag(","),
-            nom::character::complete::u64,
-        ),
-    )(data)
-}
+use crate::{
+    answer::Answer,
+    grid::{manhattan_distance, neighbors_4, Position, Size},
+    params::{Param, Params},
+    parsing::{parse_all, ParseError},
+    pathfinding::astar,
+    solution::{Example, Solution, SolveError},
+};
 
-#[inline]
-fn manhattan_distance(a: (usize, usize), b: (usize, usize)) -> usize {
-    ((a.0 as i64 - b.0 as i64).unsigned_abs() + (a.1 as i64 - b.1 as i64).unsigned_abs()) as usize
+/// The memory space of the puzzle: its size, and the number of bytes that
+/// have fallen in part 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemorySpace {
+    pub size: Size,
+    pub fallen: usize,
 }
 
-// Returns the number of steps to reach the exit, or None if there is no path
-fn has_path(
-    grid: &ArrayView2<bool>,
-    start: (usize, usize),
-    exit: (usize, usize),
-    visited: &mut Array2<Option<usize>>,
-) -> Option<usize> {
-    let grid_size = grid.dim();
-    let nrows = grid_size.0;
-    let ncols = grid_size.1;
-
-    // Let's go with A*
-    // cost (f_score), distance (g_score), (row, col)
-    #[allow(clippy::type_complexity)]
-    let mut priority_queue: BinaryHeap<Reverse<(usize, usize, (usize, usize))>> = BinaryHeap::new();
-    //let mut visited = Array2::<Option<usize>>::from_elem(grid_size, None);
-
-    let initial_cost = manhattan_distance(start, exit);
-    priority_queue.push(Reverse((initial_cost, 0, start)));
-
-    while let Some(Reverse((_f_score, g_score, (row, col)))) = priority_queue.pop() {
-        if (row, col) == exit {
-            return Some(g_score);
-        }
-
-        if let Some(visited_g_score) = visited[[row, col]] {
-            if visited_g_score <= g_score {
-                continue;
+impl MemorySpace {
+    /// The puzzle doesn't say, but the example is 7x7 with 12 bytes and
+    /// the inputs are 71x71 with 1024 bytes.
+    pub fn guess(bytes: usize) -> Self {
+        if bytes >= 1024 {
+            MemorySpace {
+                size: Size {
+                    width: 71,
+                    height: 71,
+                },
+                fallen: 1024,
+            }
+        } else {
+            MemorySpace {
+                size: Size {
+                    width: 7,
+                    height: 7,
+                },
+                fallen: 12,
             }
         }
-        visited[[row, col]] = Some(g_score);
-
-        // visit neighbors
-        for (drow, dcol) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
-            let new_row = row as i64 + drow;
-            let new_col = col as i64 + dcol;
+    }
+}
 
-            if new_row < 0 || new_row >= nrows as i64 || new_col < 0 || new_col >= ncols as i64 {
-                continue;
-            }
+/// The falling bytes as X,Y, with the memory space of the header if the
+/// input has one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Memory {
+    pub bytes: Vec<(u64, u64)>,
+    pub size: Option<Size>,
+    pub fallen: Option<usize>,
+}
 
-            let new_row = new_row as usize;
-            let new_col = new_col as usize;
+impl Memory {
+    /// The memory space from the parameters, else from the header of the
+    /// input, else guessed from the number of bytes.
+    pub fn space(&self, params: &Params) -> Result<MemorySpace, SolveError> {
+        let guess = MemorySpace::guess(self.bytes.len());
+        Ok(MemorySpace {
+            size: params.get("size")?.or(self.size).unwrap_or(guess.size),
+            fallen: params.get("bytes")?.or(self.fallen).unwrap_or(guess.fallen),
+        })
+    }
+}
 
-            if grid[[new_row, new_col]] {
-                continue;
-            }
+fn parse_size(input: &str) -> IResult<&str, Size> {
+    map(
+        verify(
+            separated_pair(
+                nom::character::complete::u64,
+                tag("x"),
+                nom::character::complete::u64,
+            ),
+            |&(width, height)| width > 0 && height > 0,
+        ),
+        |(width, height)| Size {
+            width: width as usize,
+            height: height as usize,
+        },
+    )(input)
+}
 
-            let new_g_score = g_score + 1;
-            let new_f_score = new_g_score + manhattan_distance((new_row, new_col), exit);
+fn parse_input_data(data: &str) -> IResult<&str, Memory> {
+    map(
+        tuple((
+            opt(terminated(preceded(tag("size="), parse_size), line_ending)),
+            opt(terminated(
+                preceded(tag("bytes="), nom::character::complete::u64),
+                line_ending,
+            )),
+            separated_list1(
+                line_ending,
+                separated_pair(
+                    nom::character::complete::u64,
+                    tag(","),
+                    nom::character::complete::u64,
+                ),
+            ),
+        )),
+        |(size, fallen, bytes)| Memory {
+            bytes,
+            size,
+            fallen: fallen.map(|fallen| fallen as usize),
+        },
+    )(data)
+}
 
-            priority_queue.push(Reverse((new_f_score, new_g_score, (new_row, new_col))));
-        }
+/// Returns the number of steps to reach the exit, or None if there is no path.
+/// It's an A* on the free cells of the grid (`true` is a wall).
+pub fn has_path(grid: &ArrayView2<bool>, start: Position, exit: Position) -> Option<usize> {
+    // A byte on the start blocks it as much as one on the exit
+    if grid[start] {
+        return None;
     }
-
-    None
+    astar(
+        start,
+        |&position| {
+            neighbors_4(grid.dim(), position)
+                .filter(|&neighbor| !grid[neighbor])
+                .map(|neighbor| (neighbor, 1))
+        },
+        |&position| manhattan_distance(position, exit),
+        |&position| position == exit,
+    )
+    .map(|(_, steps)| steps)
 }
 
-pub fn day_18_part_1(data: &str) -> usize {
-    let (_, data) = parse_input_data(data).expect("Failed to parse input data");
+pub fn day_18_parse(data: &str) -> Result<Memory, ParseError> {
+    parse_all(18, data, parse_input_data)
+}
 
-    // The example works on a smaller grid than the input data
-    let grid_size = if data.len() >= 1024 { (71, 71) } else { (7, 7) };
-    let max_count = if data.len() >= 1024 { 1024 } else { 12 };
+/// The bytes must all fall in the memory space, or the grid can't hold them.
+fn check_bytes(data: &[(u64, u64)], size: Size) -> Result<(), SolveError> {
+    match data
+        .iter()
+        .find(|&&(x, y)| x as usize >= size.width || y as usize >= size.height)
+    {
+        Some((x, y)) => Err(SolveError::Unsupported(format!(
+            "the byte {},{} falls outside of the {} memory space",
+            x, y, size
+        ))),
+        None => Ok(()),
+    }
+}
 
-    let nrows = grid_size.0;
-    let ncols = grid_size.1;
+pub fn day_18_part_1(data: &[(u64, u64)], space: MemorySpace) -> Result<usize, SolveError> {
+    // The grid is indexed by (x, y), it doesn't matter for a path
+    let grid_size = (space.size.width, space.size.height);
 
     let start = (0, 0);
-    let exit = (nrows - 1, ncols - 1);
+    let exit = (grid_size.0 - 1, grid_size.1 - 1);
 
+    let fallen = &data[..space.fallen.min(data.len())];
+    check_bytes(fallen, space.size)?;
     let mut grid = Array2::<bool>::from_elem(grid_size, false);
-    for &(row, col) in data[..max_count].iter() {
-        grid[[row as usize, col as usize]] = true;
+    for &(x, y) in fallen {
+        grid[[x as usize, y as usize]] = true;
     }
-    let mut visited = Array2::<Option<usize>>::from_elem(grid_size, None);
-    has_path(&grid.view(), start, exit, &mut visited).expect("No path found")
+    has_path(&grid.view(), start, exit).ok_or(SolveError::NoPath)
 }
 
-pub fn day_18_part_2(data: &str) -> String {
-    let (_, data) = parse_input_data(data).expect("Failed to parse input data");
+pub fn day_18_part_2(data: &[(u64, u64)], space: MemorySpace) -> Result<String, SolveError> {
     let ntimes = data.len();
-    let grid_size = if ntimes >= 1024 {
-        (71, 71, ntimes)
-    } else {
-        (7, 7, ntimes)
-    };
-
-    let nrows = grid_size.0;
-    let ncols = grid_size.1;
+    let grid_size = (space.size.width, space.size.height, ntimes);
 
     let start = (0, 0);
-    let exit = (nrows - 1, ncols - 1);
+    let exit = (grid_size.0 - 1, grid_size.1 - 1);
 
+    check_bytes(data, space.size)?;
     // Instead of a grid, we use a 3D cube and the third dimension is time
     let mut cube = Array3::<bool>::from_elem(grid_size, false);
-    for (time, &(row, col)) in data.iter().enumerate() {
+    for (time, &(x, y)) in data.iter().enumerate() {
         /*for t in time..ntimes {
-            cube[[row as usize, col as usize, t]] = true;
+            cube[[x as usize, y as usize, t]] = true;
         }*/
-        cube.slice_mut(s![row as usize, col as usize, time..])
+        cube.slice_mut(s![x as usize, y as usize, time..])
             .fill(true);
     }
 
     // We then do a binary search to find the first time where there is no path.
 
-    // We know that the part 1 solution is a valid path, so we don't
-    // need to check below those.
-    let mut time_low = if ntimes >= 1024 { 1024 } else { 12 };
+    // The part 1 solution is usually a valid path, so we don't need to
+    // check below those. It's only a shortcut, with more bytes than
+    // the first blocking one the whole range is searched.
+    let fallen = space.fallen.min(ntimes);
+    let mut time_low =
+        if fallen > 0 && has_path(&cube.index_axis(Axis(2), fallen - 1), start, exit).is_none() {
+            0
+        } else {
+            fallen
+        };
     // We assume that by the end of the input data, there is no path
     let mut time_high = ntimes;
 
-    let mut visited = Array2::<Option<usize>>::from_elem((nrows, ncols), None);
-
     while time_low < time_high {
         let time_pivot = time_low + (time_high - time_low) / 2;
         let grid: ArrayView2<bool> = cube.index_axis(Axis(2), time_pivot);
-        visited.fill(None);
-        if has_path(&grid, start, exit, &mut visited).is_some() {
+        if has_path(&grid, start, exit).is_some() {
             time_low = time_pivot + 1;
         } else {
             time_high = time_pivot;
         }
     }
 
-    let (row, col) = data[time_low];
-    format!("{},{}", row, col)
+    // The exit is still reachable once every byte has fallen.
+    let &(x, y) = data.get(time_low).ok_or(SolveError::NoSolution)?;
+    Ok(format!("{},{}", x, y))
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// A disjoint-set forest over the cells, with path halving and union by size.
+struct DisjointSet {
+    parents: Vec<usize>,
+    sizes: Vec<usize>,
+}
+
+impl DisjointSet {
+    fn new(len: usize) -> Self {
+        DisjointSet {
+            parents: (0..len).collect(),
+            sizes: vec![1; len],
+        }
+    }
+
+    fn find(&mut self, mut element: usize) -> usize {
+        while self.parents[element] != element {
+            self.parents[element] = self.parents[self.parents[element]];
+            element = self.parents[element];
+        }
+        element
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return;
+        }
+        if self.sizes[a] < self.sizes[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parents[b] = a;
+        self.sizes[a] += self.sizes[b];
+    }
+}
+
+/// Part 2 backwards: every byte has fallen, and they are removed from the
+/// last one until the start and the exit are connected again. The byte
+/// that connects them is the first one to block the exit.
+pub fn day_18_part_2_union_find(
+    data: &[(u64, u64)],
+    space: MemorySpace,
+) -> Result<String, SolveError> {
+    check_bytes(data, space.size)?;
+    let grid_size = (space.size.width, space.size.height);
+    let index = |(x, y): Position| x * grid_size.1 + y;
+
+    // A byte can fall twice on the same cell, it's free once both are gone
+    let mut bytes = Array2::<usize>::zeros(grid_size);
+    for &(x, y) in data {
+        bytes[[x as usize, y as usize]] += 1;
+    }
+
+    let mut cells = DisjointSet::new(grid_size.0 * grid_size.1);
+    let free = |cells: &mut DisjointSet, bytes: &Array2<usize>, position: Position| {
+        for neighbor in neighbors_4(grid_size, position) {
+            if bytes[neighbor] == 0 {
+                cells.union(index(position), index(neighbor));
+            }
+        }
+    };
+    for (position, &count) in bytes.indexed_iter() {
+        if count == 0 {
+            free(&mut cells, &bytes, position);
+        }
+    }
 
-    const EXAMPLE: &str = "5,4
+    let start = index((0, 0));
+    let exit = index((grid_size.0 - 1, grid_size.1 - 1));
+    let connected = |cells: &mut DisjointSet, bytes: &Array2<usize>| {
+        bytes[[0, 0]] == 0
+            && bytes[[grid_size.0 - 1, grid_size.1 - 1]] == 0
+            && cells.find(start) == cells.find(exit)
+    };
+
+    // The exit is still reachable once every byte has fallen.
+    if connected(&mut cells, &bytes) {
+        return Err(SolveError::NoSolution);
+    }
+
+    for &(x, y) in data.iter().rev() {
+        let position = (x as usize, y as usize);
+        bytes[position] -= 1;
+        if bytes[position] == 0 {
+            free(&mut cells, &bytes, position);
+            if connected(&mut cells, &bytes) {
+                return Ok(format!("{},{}", x, y));
+            }
+        }
+    }
+
+    unreachable!("The start and the exit are connected once no byte has fallen")
+}
+
+/// How part 2 looks for the first blocking byte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Part2Solver {
+    /// A binary search over time with A*, the first version.
+    #[default]
+    BinarySearch,
+    /// The bytes removed in reverse with a disjoint-set, in near-linear time.
+    UnionFind,
+}
+
+impl FromStr for Part2Solver {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "binary-search" => Ok(Part2Solver::BinarySearch),
+            "union-find" => Ok(Part2Solver::UnionFind),
+            _ => Err("expected binary-search or union-find".to_string()),
+        }
+    }
+}
+
+const EXAMPLE: &str = "5,4
 4,2
 4,5
 3,0
@@ -190,13 +373,206 @@ mod tests {
 1,6
 2,0";
 
+pub struct Day18;
+
+impl Solution for Day18 {
+    type Input = Memory;
+
+    fn day(&self) -> u8 {
+        18
+    }
+
+    fn name(&self) -> &'static str {
+        "RAM Run"
+    }
+
+    fn parse(&self, data: &str) -> Result<Self::Input, ParseError> {
+        day_18_parse(data)
+    }
+
+    fn part_1(&self, input: &Self::Input) -> Result<Answer, SolveError> {
+        self.solve_with(1, input, &Params::default())
+    }
+
+    fn part_2(&self, input: &Self::Input) -> Result<Answer, SolveError> {
+        self.solve_with(2, input, &Params::default())
+    }
+
+    fn params(&self) -> &'static [Param] {
+        &[
+            Param {
+                name: "size",
+                help: "memory space as WIDTHxHEIGHT",
+            },
+            Param {
+                name: "bytes",
+                help: "bytes fallen in part 1",
+            },
+            Param {
+                name: "solver",
+                help: "part 2 with binary-search or union-find",
+            },
+        ]
+    }
+
+    fn solve_with(
+        &self,
+        part: u8,
+        input: &Self::Input,
+        params: &Params,
+    ) -> Result<Answer, SolveError> {
+        let space = input.space(params)?;
+        match part {
+            1 => Ok(day_18_part_1(&input.bytes, space)?.into()),
+            _ => match params.get("solver")?.unwrap_or_default() {
+                Part2Solver::BinarySearch => Ok(day_18_part_2(&input.bytes, space)?.into()),
+                Part2Solver::UnionFind => Ok(day_18_part_2_union_find(&input.bytes, space)?.into()),
+            },
+        }
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            data: EXAMPLE,
+            part_1: Some("22"),
+            part_2: Some("6,1"),
+            params: &[("size", "7x7"), ("bytes", "12")],
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn guessed_space(memory: &Memory) -> MemorySpace {
+        memory.space(&Params::default()).unwrap()
+    }
+
     #[test]
     fn test_day_18_part_1() {
-        assert_eq!(day_18_part_1(EXAMPLE), 22);
+        let memory = day_18_parse(EXAMPLE).unwrap();
+        assert_eq!(day_18_part_1(&memory.bytes, guessed_space(&memory)), Ok(22));
     }
 
     #[test]
     fn test_day_18_part_2() {
-        assert_eq!(day_18_part_2(EXAMPLE), "6,1");
+        let memory = day_18_parse(EXAMPLE).unwrap();
+        assert_eq!(
+            day_18_part_2(&memory.bytes, guessed_space(&memory)),
+            Ok("6,1".to_string())
+        );
+        // More bytes than the first blocking one, 6,1 is the 21st
+        for bytes in [21, 24] {
+            let space = MemorySpace {
+                fallen: bytes,
+                ..guessed_space(&memory)
+            };
+            assert_eq!(day_18_part_2(&memory.bytes, space), Ok("6,1".to_string()));
+        }
+    }
+
+    #[test]
+    fn test_day_18_part_2_union_find() {
+        let memory = day_18_parse(EXAMPLE).unwrap();
+        assert_eq!(
+            day_18_part_2_union_find(&memory.bytes, guessed_space(&memory)),
+            Ok("6,1".to_string())
+        );
+        let open = day_18_parse("2,2\n3,3\n2,2").unwrap();
+        assert_eq!(
+            day_18_part_2_union_find(&open.bytes, guessed_space(&open)),
+            Err(SolveError::NoSolution)
+        );
+        // blocking the start counts too
+        let start = day_18_parse("3,3\n0,0\n4,4").unwrap();
+        assert_eq!(
+            day_18_part_2_union_find(&start.bytes, guessed_space(&start)),
+            Ok("0,0".to_string())
+        );
+
+        let mut params = Params::default();
+        params.set("solver", "union-find");
+        assert_eq!(
+            Day18.solve_with(2, &memory, &params),
+            Ok(Answer::from("6,1".to_string()))
+        );
+        params.set("solver", "guess");
+        assert!(Day18.solve_with(2, &memory, &params).is_err());
+    }
+
+    #[test]
+    fn test_day_18_part_2_solvers_agree() {
+        for data in [EXAMPLE, "2,2\n3,3\n2,2", "3,3\n0,0\n4,4", "3,3\n6,6\n4,4"] {
+            let memory = day_18_parse(data).unwrap();
+            let space = guessed_space(&memory);
+            assert_eq!(
+                day_18_part_2(&memory.bytes, space),
+                day_18_part_2_union_find(&memory.bytes, space),
+                "{:?}",
+                data
+            );
+        }
+    }
+
+    #[test]
+    fn test_day_18_memory_space() {
+        let memory = day_18_parse(EXAMPLE).unwrap();
+        assert_eq!(memory.size, None);
+        assert_eq!(guessed_space(&memory), MemorySpace::guess(25));
+
+        // The header of the input comes before the guess
+        let memory = day_18_parse(&format!("size=8x7\nbytes=3\n{}", EXAMPLE)).unwrap();
+        let space = guessed_space(&memory);
+        assert_eq!(
+            space,
+            MemorySpace {
+                size: Size {
+                    width: 8,
+                    height: 7
+                },
+                fallen: 3
+            }
+        );
+        assert_eq!(day_18_part_1(&memory.bytes, space), Ok(13));
+
+        // and the parameters before the header
+        let mut params = Params::default();
+        params.set("size", "7x7");
+        params.set("bytes", "12");
+        assert_eq!(
+            Day18.solve_with(1, &memory, &params),
+            Ok(Answer::from(22usize))
+        );
+        params.set("size", "seven");
+        assert!(matches!(
+            Day18.solve_with(1, &memory, &params),
+            Err(SolveError::InvalidParameter(_))
+        ));
+    }
+
+    #[test]
+    fn test_day_18_errors() {
+        let walled = day_18_parse("0,1\n1,1\n1,0").unwrap();
+        assert_eq!(
+            day_18_part_1(&walled.bytes, guessed_space(&walled)),
+            Err(SolveError::NoPath)
+        );
+        let start = day_18_parse("3,3\n0,0\n4,4").unwrap();
+        assert_eq!(
+            day_18_part_1(&start.bytes, guessed_space(&start)),
+            Err(SolveError::NoPath)
+        );
+        let open = day_18_parse("2,2\n3,3").unwrap();
+        assert_eq!(
+            day_18_part_2(&open.bytes, guessed_space(&open)),
+            Err(SolveError::NoSolution)
+        );
+        let outside = day_18_parse("7,0").unwrap();
+        assert!(matches!(
+            day_18_part_1(&outside.bytes, guessed_space(&outside)),
+            Err(SolveError::Unsupported(_))
+        ));
+        assert!(day_18_parse("size=0x7\n1,1").is_err());
     }
 }
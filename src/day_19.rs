@@ -21,6 +21,12 @@ use nom::{
 use rayon::iter::IntoParallelRefIterator;
 use rayon::iter::ParallelIterator;
 
+use crate::{
+    answer::Answer,
+    parsing::{parse_all, ParseError},
+    solution::{Example, Solution, SolveError},
+};
+
 type Pattern = Vec<char>;
 
 fn parse_pattern(data: &str) -> IResult<&str, Pattern> {
@@ -43,15 +49,17 @@ fn parse_input_data(data: &str) -> IResult<&str, (Vec<Pattern>, Vec<Pattern>)> {
     )(data)
 }
 
-pub fn day_19_part_1(data: &str) -> usize {
-    let (_, (patterns, designs)) = parse_input_data(data).expect("Failed to parse input data");
+pub fn day_19_parse(data: &str) -> Result<(Vec<Pattern>, Vec<Pattern>), ParseError> {
+    parse_all(19, data, parse_input_data)
+}
 
+pub fn day_19_part_1((patterns, designs): &(Vec<Pattern>, Vec<Pattern>)) -> usize {
     let mut regex_string = String::new();
     regex_string.push_str("^(");
 
     for pattern in patterns {
         for c in pattern {
-            regex_string.push(c);
+            regex_string.push(*c);
         }
         regex_string.push('|');
     }
@@ -86,9 +94,7 @@ fn count_number_of_possibilities(design: String, patterns: &[String]) -> usize {
     count
 }
 
-pub fn day_19_part_2(data: &str) -> usize {
-    let (_, (patterns, designs)) = parse_input_data(data).expect("Failed to parse input data");
-
+pub fn day_19_part_2((patterns, designs): &(Vec<Pattern>, Vec<Pattern>)) -> usize {
     // we prefer strings to vectors of chars
     let patterns = patterns
         .iter()
@@ -138,11 +144,7 @@ pub fn day_19_part_2(data: &str) -> usize {
         .sum()
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const EXAMPLE: &str = "r, wr, b, g, bwu, rb, gb, br
+const EXAMPLE: &str = "r, wr, b, g, bwu, rb, gb, br
 
 brwrr
 bggr
@@ -153,13 +155,52 @@ bwurrg
 brgr
 bbrgwb";
 
+pub struct Day19;
+
+impl Solution for Day19 {
+    type Input = (Vec<Pattern>, Vec<Pattern>);
+
+    fn day(&self) -> u8 {
+        19
+    }
+
+    fn name(&self) -> &'static str {
+        "Linen Layout"
+    }
+
+    fn parse(&self, data: &str) -> Result<Self::Input, ParseError> {
+        day_19_parse(data)
+    }
+
+    fn part_1(&self, input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(day_19_part_1(input).into())
+    }
+
+    fn part_2(&self, input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(day_19_part_2(input).into())
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            data: EXAMPLE,
+            part_1: Some("6"),
+            part_2: Some("16"),
+            params: &[],
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     #[test]
     fn test_day_19_part_1() {
-        assert_eq!(day_19_part_1(EXAMPLE), 6);
+        assert_eq!(day_19_part_1(&day_19_parse(EXAMPLE).unwrap()), 6);
     }
 
     #[test]
     fn test_day_19_part_2() {
-        assert_eq!(day_19_part_2(EXAMPLE), 16);
+        assert_eq!(day_19_part_2(&day_19_parse(EXAMPLE).unwrap()), 16);
     }
 }
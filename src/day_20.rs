@@ -37,59 +37,51 @@
     this is good enough for now and I'm done with this puzzle.
 */
 use ndarray::{Array2, ArrayView2};
-use nom::{
-    branch::alt,
-    bytes::complete::tag,
-    character::complete::line_ending,
-    combinator::map,
-    multi::{many1, separated_list1},
-    IResult,
-};
+use nom::IResult;
 use rayon::iter::IntoParallelRefIterator;
 use rayon::iter::ParallelIterator;
 
+use crate::{
+    answer::Answer,
+    grid::{
+        find_start_and_exit, manhattan_distance, neighbors_4, offset_position, parse_cells,
+        GridCell,
+    },
+    parsing::{parse_all, ParseError},
+    solution::{Solution, SolveError},
+};
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum Cell {
+pub enum Cell {
     Empty,
     Wall,
     Start,
     Exit,
 }
 
-fn parse_cell(input: &str) -> IResult<&str, Cell> {
-    alt((
-        map(tag("#"), |_| Cell::Wall),
-        map(tag("S"), |_| Cell::Start),
-        map(tag("E"), |_| Cell::Exit),
-        map(tag("."), |_| Cell::Empty),
-    ))(input)
-}
-
-fn parse_input_data(input: &str) -> IResult<&str, Array2<Cell>> {
-    map(separated_list1(line_ending, many1(parse_cell)), |grid| {
-        let nb_rows = grid.len();
-        let nb_cols = grid.first().map_or(0, |row| row.len());
-
-        Array2::from_shape_fn((nb_rows, nb_cols), |(row, col)| grid[row][col])
-    })(input)
-}
-
-fn find_start_and_exit(map: &Array2<Cell>) -> ((usize, usize), (usize, usize)) {
-    let mut start: Option<(usize, usize)> = None;
-    let mut exit: Option<(usize, usize)> = None;
+impl GridCell for Cell {
+    fn from_char(c: char) -> Option<Self> {
+        match c {
+            '#' => Some(Cell::Wall),
+            'S' => Some(Cell::Start),
+            'E' => Some(Cell::Exit),
+            '.' => Some(Cell::Empty),
+            _ => None,
+        }
+    }
 
-    for (position, &cell) in map.indexed_iter() {
-        if cell == Cell::Start {
-            start = Some(position);
-        } else if cell == Cell::Exit {
-            exit = Some(position);
+    fn to_char(&self) -> char {
+        match self {
+            Cell::Empty => '.',
+            Cell::Wall => '#',
+            Cell::Start => 'S',
+            Cell::Exit => 'E',
         }
     }
+}
 
-    (
-        start.expect("Failed to find start cell"),
-        exit.expect("Failed to find exit cell"),
-    )
+fn parse_input_data(input: &str) -> IResult<&str, Array2<Cell>> {
+    parse_cells(input)
 }
 
 #[allow(clippy::type_complexity)]
@@ -99,7 +91,6 @@ fn compute_path_lengths(
     end: (usize, usize),
 ) -> (Array2<Option<usize>>, Vec<((usize, usize), usize)>) {
     let map_size = map.dim();
-    let (nrows, ncols) = map_size;
 
     let mut current = start;
     let mut previous = start;
@@ -113,22 +104,11 @@ fn compute_path_lengths(
         lol.push((current, current_length));
         current_length += 1;
 
-        let (row, col) = current;
         // find the next non wall cell
-        for (drow, dcol) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
-            let new_row = row as i64 + drow;
-            let new_col = col as i64 + dcol;
-
-            if new_row < 0 || new_row >= nrows as i64 || new_col < 0 || new_col >= ncols as i64 {
-                continue;
-            }
-
-            let new_row = new_row as usize;
-            let new_col = new_col as usize;
-
-            if map[[new_row, new_col]] != Cell::Wall && (new_row, new_col) != previous {
+        for neighbor in neighbors_4(map_size, current) {
+            if map[neighbor] != Cell::Wall && neighbor != previous {
                 previous = current;
-                current = (new_row, new_col);
+                current = neighbor;
                 break;
             }
         }
@@ -139,15 +119,14 @@ fn compute_path_lengths(
     (path_lengths, lol)
 }
 
-fn compute_part_1(data: &str, threshold: usize) -> usize {
-    let (_, map) = parse_input_data(data).expect("Failed to parse input data");
-    let (start, exit) = find_start_and_exit(&map);
+fn compute_part_1(map: &Array2<Cell>, threshold: usize) -> Result<usize, SolveError> {
+    let (start, exit) = find_start_and_exit(map, &Cell::Start, &Cell::Exit)?;
     let map_view = map.view();
     let (path_lengths, _) = compute_path_lengths(&map_view, start, exit);
 
     //let non_cheating_length = path_lengths[exit].expect("Failed to find a path without cheats");
 
-    path_lengths
+    Ok(path_lengths
         .windows((1, 3))
         .into_iter()
         .filter(|w| {
@@ -176,28 +155,26 @@ fn compute_part_1(data: &str, threshold: usize) -> usize {
 
                 a.unwrap().abs_diff(c.unwrap()) > threshold
             })
-            .count()
+            .count())
 }
-pub fn day_20_part_1(data: &str) -> usize {
-    compute_part_1(data, 100)
+
+pub fn day_20_parse(data: &str) -> Result<Array2<Cell>, ParseError> {
+    parse_all(20, data, parse_input_data)
 }
 
-#[inline]
-fn manhattan_distance(a: (usize, usize), b: (usize, usize)) -> usize {
-    a.0.abs_diff(b.0) + a.1.abs_diff(b.1)
+pub fn day_20_part_1(map: &Array2<Cell>) -> Result<usize, SolveError> {
+    compute_part_1(map, 100)
 }
 
-fn compute_part_2(data: &str, threshold: usize) -> usize {
-    let (_, map) = parse_input_data(data).expect("Failed to parse input data");
-    let (start, exit) = find_start_and_exit(&map);
+fn compute_part_2(map: &Array2<Cell>, threshold: usize) -> Result<usize, SolveError> {
+    let (start, exit) = find_start_and_exit(map, &Cell::Start, &Cell::Exit)?;
     let map_view = map.view();
     let (path_lengths, perfect_path) = compute_path_lengths(&map_view, start, exit);
     let map_size = map.dim();
-    let (nrows, ncols) = map_size;
     let window_size = 21_isize;
     let neg_window_size = -21_isize;
 
-    perfect_path
+    Ok(perfect_path
         .par_iter()
         .map(|(position_start, window_start)| {
             /*let window_start = if let Some(window_start) = window_start {
@@ -209,8 +186,6 @@ fn compute_part_2(data: &str, threshold: usize) -> usize {
             let position_start = *position_start;
             let window_start = *window_start;
 
-            let (start_row, start_col) = position_start;
-
             let mut count = 0;
 
             for drow in neg_window_size..window_size {
@@ -218,21 +193,11 @@ fn compute_part_2(data: &str, threshold: usize) -> usize {
                     if drow.abs() + dcol.abs() >= window_size {
                         continue;
                     }
-                    let end_row: isize = start_row as isize + drow;
-                    let end_col: isize = start_col as isize + dcol;
-
-                    if end_row < 0
-                        || end_col < 0
-                        || end_row >= nrows as isize
-                        || end_col >= ncols as isize
-                    {
+                    let Some(position_end) =
+                        offset_position(map_size, position_start, (drow, dcol))
+                    else {
                         continue;
-                    }
-
-                    let end_row = end_row as usize;
-                    let end_col = end_col as usize;
-
-                    let position_end = (end_row, end_col);
+                    };
 
                     if position_start == position_end {
                         continue;
@@ -264,11 +229,37 @@ fn compute_part_2(data: &str, threshold: usize) -> usize {
 
             count
         })
-        .sum()
+        .sum())
 }
 
-pub fn day_20_part_2(data: &str) -> usize {
-    compute_part_2(data, 100)
+pub fn day_20_part_2(map: &Array2<Cell>) -> Result<usize, SolveError> {
+    compute_part_2(map, 100)
+}
+
+pub struct Day20;
+
+impl Solution for Day20 {
+    type Input = Array2<Cell>;
+
+    fn day(&self) -> u8 {
+        20
+    }
+
+    fn name(&self) -> &'static str {
+        "Race Condition"
+    }
+
+    fn parse(&self, data: &str) -> Result<Self::Input, ParseError> {
+        day_20_parse(data)
+    }
+
+    fn part_1(&self, input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(day_20_part_1(input)?.into())
+    }
+
+    fn part_2(&self, input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(day_20_part_2(input)?.into())
+    }
 }
 
 #[cfg(test)]
@@ -293,20 +284,20 @@ mod tests {
 
     #[test]
     fn test_day_20_part_1() {
-        assert_eq!(compute_part_1(EXAMPLE, 0), 44);
-        assert_eq!(compute_part_1(EXAMPLE, 20), 5);
-        assert_eq!(day_20_part_1(EXAMPLE), 0);
+        assert_eq!(compute_part_1(&day_20_parse(EXAMPLE).unwrap(), 0), Ok(44));
+        assert_eq!(compute_part_1(&day_20_parse(EXAMPLE).unwrap(), 20), Ok(5));
+        assert_eq!(day_20_part_1(&day_20_parse(EXAMPLE).unwrap()), Ok(0));
     }
 
     #[test]
     fn test_day_20_part_2() {
-        assert_eq!(compute_part_2(EXAMPLE, 80), 0);
-        assert_eq!(compute_part_2(EXAMPLE, 76), 3);
-        assert_eq!(compute_part_2(EXAMPLE, 74), 7);
-        assert_eq!(compute_part_2(EXAMPLE, 72), 29);
-        assert_eq!(compute_part_2(EXAMPLE, 70), 41);
-        assert_eq!(compute_part_2(EXAMPLE, 50), 285);
-        assert_eq!(day_20_part_2(EXAMPLE), 0);
+        assert_eq!(compute_part_2(&day_20_parse(EXAMPLE).unwrap(), 80), Ok(0));
+        assert_eq!(compute_part_2(&day_20_parse(EXAMPLE).unwrap(), 76), Ok(3));
+        assert_eq!(compute_part_2(&day_20_parse(EXAMPLE).unwrap(), 74), Ok(7));
+        assert_eq!(compute_part_2(&day_20_parse(EXAMPLE).unwrap(), 72), Ok(29));
+        assert_eq!(compute_part_2(&day_20_parse(EXAMPLE).unwrap(), 70), Ok(41));
+        assert_eq!(compute_part_2(&day_20_parse(EXAMPLE).unwrap(), 50), Ok(285));
+        assert_eq!(day_20_part_2(&day_20_parse(EXAMPLE).unwrap()), Ok(0));
 
         // 961364
     }
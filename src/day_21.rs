@@ -39,6 +39,13 @@ use nom::{
 };
 use std::collections::HashMap;
 
+use crate::{
+    answer::Answer,
+    direction::Direction,
+    parsing::{parse_all, ParseError},
+    solution::{Example, Solution, SolveError},
+};
+
 /*
 +---+---+---+
 | 7 | 8 | 9 | 0
@@ -65,92 +72,74 @@ static NUMPAD_8: PadPosition = (0, 1);
 static NUMPAD_9: PadPosition = (0, 2);
 static NUMPAD_A: PadPosition = (3, 2);
 
-#[inline]
-fn char_to_keypad_position(c: char) -> PadPosition {
-    match c {
-        '0' => NUMPAD_0,
-        '1' => NUMPAD_1,
-        '2' => NUMPAD_2,
-        '3' => NUMPAD_3,
-        '4' => NUMPAD_4,
-        '5' => NUMPAD_5,
-        '6' => NUMPAD_6,
-        '7' => NUMPAD_7,
-        '8' => NUMPAD_8,
-        '9' => NUMPAD_9,
-        'A' => NUMPAD_A,
-        _ => panic!("Invalid character in sequence"),
-    }
-}
+/// The keys of the numeric keypad, with their positions.
+static KEYPAD: &[(char, PadPosition)] = &[
+    ('0', NUMPAD_0),
+    ('1', NUMPAD_1),
+    ('2', NUMPAD_2),
+    ('3', NUMPAD_3),
+    ('4', NUMPAD_4),
+    ('5', NUMPAD_5),
+    ('6', NUMPAD_6),
+    ('7', NUMPAD_7),
+    ('8', NUMPAD_8),
+    ('9', NUMPAD_9),
+    ('A', NUMPAD_A),
+];
 
 type PadPaths = HashMap<(char, char), Vec<String>>;
-#[cached]
-fn build_keypad_paths() -> PadPaths {
-    // We build with an algorithm because it's actually 90 possible paths
-    static CHARS: &[char] = &['0', '1', '2', '3', '4', '5', '6', '7', '8', '9', 'A'];
+
+/// The paths between every pair of keys, going horizontal first or vertical
+/// first, but never over the gap of the pad.
+fn build_pad_paths(keys: &[(char, PadPosition)], gap: PadPosition) -> PadPaths {
     let mut pad_paths = HashMap::new();
 
-    for &char_start in CHARS {
-        for &char_stop in CHARS {
-            //if char_start != '0' || char_stop != '4' {
-            //    continue;
-            // }
-            let position_start = char_to_keypad_position(char_start);
-            let position_stop = char_to_keypad_position(char_stop);
-            let (row_start, col_start) = position_start;
-            let (row_stop, col_stop) = position_stop;
-
-            let direction_row = if row_start < row_stop { 1 } else { -1 };
-            let direction_col = if col_start < col_stop { 1 } else { -1 };
-            let letter_row = if direction_row == 1 { 'v' } else { '^' };
-            let letter_col = if direction_col == 1 { '>' } else { '<' };
+    for &(char_start, (row_start, col_start)) in keys {
+        for &(char_stop, (row_stop, col_stop)) in keys {
+            let direction_row = if row_start < row_stop {
+                Direction::Down
+            } else {
+                Direction::Up
+            };
+            let direction_col = if col_start < col_stop {
+                Direction::Right
+            } else {
+                Direction::Left
+            };
+            let moves_row = direction_row
+                .to_arrow()
+                .to_string()
+                .repeat(row_start.abs_diff(row_stop));
+            let moves_col = direction_col
+                .to_arrow()
+                .to_string()
+                .repeat(col_start.abs_diff(col_stop));
 
             let mut route_paths = Vec::with_capacity(2);
 
             // horizontal first path
-            if row_start != 3 || col_stop != 0 {
-                let mut horizontal_first_path = String::new();
-                let mut current_row = row_start;
-                let mut current_col = col_start;
-                while current_col != col_stop {
-                    horizontal_first_path.push(letter_col);
-                    current_col = (current_col as i64 + direction_col) as usize;
-                }
-                while current_row != row_stop {
-                    horizontal_first_path.push(letter_row);
-                    current_row = (current_row as i64 + direction_row) as usize;
-                }
-                horizontal_first_path.push('A');
-                route_paths.push(horizontal_first_path);
+            if (row_start, col_stop) != gap {
+                route_paths.push(format!("{}{}A", moves_col, moves_row));
             }
 
             // vertical first path
-            if col_start != 0 || row_stop != 3 {
-                let mut vertical_first_path = String::new();
-                let mut current_row = row_start;
-                let mut current_col = col_start;
-                while current_row != row_stop {
-                    vertical_first_path.push(letter_row);
-                    current_row = (current_row as i64 + direction_row) as usize;
-                }
-                while current_col != col_stop {
-                    vertical_first_path.push(letter_col);
-                    current_col = (current_col as i64 + direction_col) as usize;
-                }
-                vertical_first_path.push('A');
-                route_paths.push(vertical_first_path);
+            if (row_stop, col_start) != gap {
+                route_paths.push(format!("{}{}A", moves_row, moves_col));
             }
 
             pad_paths.insert((char_start, char_stop), route_paths);
         }
     }
 
-    //println!("{:?}", keypad);
-    //println!("{:?}", paths);
-
     pad_paths
 }
 
+#[cached]
+fn build_keypad_paths() -> PadPaths {
+    // We build with an algorithm because it's actually 90 possible paths
+    build_pad_paths(KEYPAD, (3, 0))
+}
+
 //let new_position = char_to_keypad_position(c);
 
 /*
@@ -168,75 +157,18 @@ static DIRECTIONAL_PAD_LEFT: PadPosition = (1, 0);
 static DIRECTIONAL_PAD_RIGHT: PadPosition = (1, 2);
 static DIRECTIONAL_PAD_A: PadPosition = (0, 2);
 
-#[inline]
-fn char_to_directional_pad_position(c: char) -> PadPosition {
-    match c {
-        '^' => DIRECTIONAL_PAD_UP,
-        'v' => DIRECTIONAL_PAD_DOWN,
-        '<' => DIRECTIONAL_PAD_LEFT,
-        '>' => DIRECTIONAL_PAD_RIGHT,
-        'A' => DIRECTIONAL_PAD_A,
-        _ => panic!("Invalid character in sequence"),
-    }
-}
+/// The keys of the directional pad, with their positions.
+static DIRECTIONAL_PAD: &[(char, PadPosition)] = &[
+    ('^', DIRECTIONAL_PAD_UP),
+    ('v', DIRECTIONAL_PAD_DOWN),
+    ('<', DIRECTIONAL_PAD_LEFT),
+    ('>', DIRECTIONAL_PAD_RIGHT),
+    ('A', DIRECTIONAL_PAD_A),
+];
 
 #[cached]
 fn build_directional_pad_paths() -> PadPaths {
-    static CHARS: &[char] = &['^', 'v', '<', '>', 'A'];
-    let mut pad_paths = HashMap::new();
-
-    for &char_start in CHARS {
-        for &char_stop in CHARS {
-            let position_start = char_to_directional_pad_position(char_start);
-            let position_stop = char_to_directional_pad_position(char_stop);
-            let (row_start, col_start) = position_start;
-            let (row_stop, col_stop) = position_stop;
-
-            let direction_row = if row_start < row_stop { 1 } else { -1 };
-            let direction_col = if col_start < col_stop { 1 } else { -1 };
-            let letter_row = if direction_row == 1 { 'v' } else { '^' };
-            let letter_col = if direction_col == 1 { '>' } else { '<' };
-
-            let mut route_paths = Vec::with_capacity(2);
-
-            // horizontal first path
-            if row_start != 0 || col_stop != 0 {
-                let mut horizontal_first_path = String::new();
-                let mut current_row = row_start;
-                let mut current_col = col_start;
-                while current_col != col_stop {
-                    horizontal_first_path.push(letter_col);
-                    current_col = (current_col as i64 + direction_col) as usize;
-                }
-                while current_row != row_stop {
-                    horizontal_first_path.push(letter_row);
-                    current_row = (current_row as i64 + direction_row) as usize;
-                }
-                horizontal_first_path.push('A');
-                route_paths.push(horizontal_first_path);
-            }
-            // vertical first path
-            if col_start != 0 || row_stop != 0 {
-                let mut vertical_first_path = String::new();
-                let mut current_row = row_start;
-                let mut current_col = col_start;
-                while current_row != row_stop {
-                    vertical_first_path.push(letter_row);
-                    current_row = (current_row as i64 + direction_row) as usize;
-                }
-                while current_col != col_stop {
-                    vertical_first_path.push(letter_col);
-                    current_col = (current_col as i64 + direction_col) as usize;
-                }
-                vertical_first_path.push('A');
-                route_paths.push(vertical_first_path);
-            }
-            pad_paths.insert((char_start, char_stop), route_paths);
-        }
-    }
-    //println!("{:?}", keypad);
-    //println!("{:?}", paths);
-    pad_paths
+    build_pad_paths(DIRECTIONAL_PAD, (0, 0))
 }
 
 fn rec_compute_minimum_pad_sequence(
@@ -245,33 +177,33 @@ fn rec_compute_minimum_pad_sequence(
     current_path: &String,
     results: &mut Vec<String>,
     paths: &PadPaths,
-) {
-    if sequence_left.is_empty() {
+) -> Result<(), SolveError> {
+    let mut letters = sequence_left.chars();
+    let Some(next_letter) = letters.next() else {
         results.push(current_path.clone());
-        return;
-    }
+        return Ok(());
+    };
 
-    let next_letter = sequence_left.chars().next().unwrap();
-    let next_path = &paths[&(previous_letter, next_letter)];
+    let next_path = paths.get(&(previous_letter, next_letter)).ok_or_else(|| {
+        SolveError::Unsupported(format!("there is no key {} on the pad", next_letter))
+    })?;
     for path in next_path {
         let mut new_path = current_path.to_string();
         new_path.push_str(path);
-        rec_compute_minimum_pad_sequence(
-            &sequence_left[1..],
-            next_letter,
-            &new_path,
-            results,
-            paths,
-        );
+        rec_compute_minimum_pad_sequence(letters.as_str(), next_letter, &new_path, results, paths)?;
     }
+    Ok(())
 }
 
-fn compute_minimum_pad_sequences(sequence: &str, paths: &PadPaths) -> Vec<String> {
+fn compute_minimum_pad_sequences(
+    sequence: &str,
+    paths: &PadPaths,
+) -> Result<Vec<String>, SolveError> {
     let start_letter: char = 'A';
 
     let mut results = Vec::new();
-    rec_compute_minimum_pad_sequence(sequence, start_letter, &"".to_string(), &mut results, paths);
-    results
+    rec_compute_minimum_pad_sequence(sequence, start_letter, &"".to_string(), &mut results, paths)?;
+    Ok(results)
 }
 
 fn parse_input_data(data: &str) -> IResult<&str, Vec<(String, usize)>> {
@@ -324,65 +256,103 @@ fn parse_input_data(data: &str) -> IResult<&str, Vec<(String, usize)>> {
 }*/
 
 #[cached(key = "(String, usize)", convert = "{ (keys.clone(), depth) }")]
-fn shortest_sequence(keys: String, depth: usize, paths: &PadPaths) -> usize {
+fn shortest_sequence(keys: String, depth: usize, paths: &PadPaths) -> Result<usize, SolveError> {
     if depth == 0 {
-        return keys.len();
+        return Ok(keys.len());
     }
 
-    // split per A, split inclusive is A MUST
+    // split per A, split inclusive is A MUST
     let sub_keys: Vec<&str> = keys.split_inclusive('A').collect();
     let mut total = 0;
     for sub_key in sub_keys {
-        let sequences = compute_minimum_pad_sequences(sub_key, paths);
+        let sequences = compute_minimum_pad_sequences(sub_key, paths)?;
         let mut min_length = usize::MAX;
         for sequence in sequences {
-            let length = shortest_sequence(sequence, depth - 1, paths);
+            let length = shortest_sequence(sequence, depth - 1, paths)?;
             if length < min_length {
                 min_length = length;
             }
         }
         total += min_length;
     }
-    total
+    Ok(total)
 }
 
-fn compute_day_21(data: &str, depth: usize) -> usize {
-    let (_, data) = parse_input_data(data).expect("Failed to parse input data");
+fn compute_day_21(data: &[(String, usize)], depth: usize) -> Result<usize, SolveError> {
     let keypad_paths = build_keypad_paths();
     let directional_pad_paths = build_directional_pad_paths();
     data.iter()
         .map(|(sequence, value)| {
             let mut shortest = usize::MAX;
-            let keypad_sequences = compute_minimum_pad_sequences(sequence, &keypad_paths);
+            let keypad_sequences = compute_minimum_pad_sequences(sequence, &keypad_paths)?;
             for keypad_sequence in keypad_sequences {
                 let sequence_length =
-                    shortest_sequence(keypad_sequence, depth, &directional_pad_paths);
+                    shortest_sequence(keypad_sequence, depth, &directional_pad_paths)?;
                 if sequence_length < shortest {
                     shortest = sequence_length;
                 }
             }
-            shortest * *value
+            Ok(shortest * *value)
         })
         .sum()
 }
-pub fn day_21_part_1(data: &str) -> usize {
+pub fn day_21_parse(data: &str) -> Result<Vec<(String, usize)>, ParseError> {
+    parse_all(21, data, parse_input_data)
+}
+
+pub fn day_21_part_1(data: &[(String, usize)]) -> Result<usize, SolveError> {
     compute_day_21(data, 2)
 }
 
-pub fn day_21_part_2(data: &str) -> usize {
+pub fn day_21_part_2(data: &[(String, usize)]) -> Result<usize, SolveError> {
     compute_day_21(data, 25)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const EXAMPLE: &str = "029A
+const EXAMPLE: &str = "029A
 980A
 179A
 456A
 379A";
 
+pub struct Day21;
+
+impl Solution for Day21 {
+    type Input = Vec<(String, usize)>;
+
+    fn day(&self) -> u8 {
+        21
+    }
+
+    fn name(&self) -> &'static str {
+        "Keypad Conundrum"
+    }
+
+    fn parse(&self, data: &str) -> Result<Self::Input, ParseError> {
+        day_21_parse(data)
+    }
+
+    fn part_1(&self, input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(day_21_part_1(input)?.into())
+    }
+
+    fn part_2(&self, input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(day_21_part_2(input)?.into())
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            data: EXAMPLE,
+            part_1: Some("126384"),
+            part_2: Some("154115708116294"),
+            params: &[],
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     /*#[test]
     fn test_compute_minimum_keypad_sequence_length() {
         assert_eq!(compute_minimum_keypad_sequence_length("0"), 2);
@@ -421,8 +391,12 @@ mod tests {
         );*/
         assert_eq!(
             compute_minimum_pad_sequences("<A", &paths),
-            vec!["v<<A>>^A"]
+            Ok(vec!["v<<A>>^A".to_string()])
         );
+        assert!(matches!(
+            compute_minimum_pad_sequences("<7", &paths),
+            Err(SolveError::Unsupported(_))
+        ));
     }
 
     /*#[test]
@@ -446,11 +420,14 @@ mod tests {
 
     #[test]
     fn test_day_21_part_1() {
-        assert_eq!(day_21_part_1(EXAMPLE), 126384);
+        assert_eq!(day_21_part_1(&day_21_parse(EXAMPLE).unwrap()), Ok(126384));
     }
 
     #[test]
     fn test_day_21_part_2() {
-        assert_eq!(day_21_part_2(EXAMPLE), 154115708116294);
+        assert_eq!(
+            day_21_part_2(&day_21_parse(EXAMPLE).unwrap()),
+            Ok(154115708116294)
+        );
     }
 }
@@ -33,6 +33,12 @@ use rayon::{
     slice::ParallelSlice,
 };
 
+use crate::{
+    answer::Answer,
+    parsing::{parse_all, ParseError},
+    solution::{Example, Solution, SolveError},
+};
+
 #[inline]
 fn mix(a: u32, b: u32) -> u32 {
     a ^ b
@@ -69,9 +75,11 @@ fn parse_input_data(data: &str) -> IResult<&str, Vec<u32>> {
     separated_list0(line_ending, nom::character::complete::u32)(data)
 }
 
-pub fn day_22_part_1(data: &str) -> i64 {
-    let (_, data) = parse_input_data(data).expect("Failed to parse input data");
+pub fn day_22_parse(data: &str) -> Result<Vec<u32>, ParseError> {
+    parse_all(22, data, parse_input_data)
+}
 
+pub fn day_22_part_1(data: &[u32]) -> i64 {
     data.par_iter()
         .map(|seed| {
             let mut secret = *seed;
@@ -98,9 +106,7 @@ fn last_digit_base_10(number: u32) -> i8 {
     (number % 10) as i8
 }
 
-pub fn day_22_part_2(data: &str) -> i64 {
-    let (_, data) = parse_input_data(data).expect("Failed to parse input data");
-
+pub fn day_22_part_2(data: &[u32]) -> i64 {
     let max_iter = 2000 - 3;
     let chunk_size = 64;
     //let array_size = 1 << 20;
@@ -168,20 +174,63 @@ pub fn day_22_part_2(data: &str) -> i64 {
         .unwrap_or(&0) as i64
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const EXAMPLE_PART_1: &str = "1
+const EXAMPLE_PART_1: &str = "1
 10
 100
 2024";
 
-    const EXAMPLE_PART_2: &str = "1
+const EXAMPLE_PART_2: &str = "1
 2
 3
 2024";
 
+pub struct Day22;
+
+impl Solution for Day22 {
+    type Input = Vec<u32>;
+
+    fn day(&self) -> u8 {
+        22
+    }
+
+    fn name(&self) -> &'static str {
+        "Monkey Market"
+    }
+
+    fn parse(&self, data: &str) -> Result<Self::Input, ParseError> {
+        day_22_parse(data)
+    }
+
+    fn part_1(&self, input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(day_22_part_1(input).into())
+    }
+
+    fn part_2(&self, input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(day_22_part_2(input).into())
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                data: EXAMPLE_PART_1,
+                part_1: Some("37327623"),
+                part_2: None,
+                params: &[],
+            },
+            Example {
+                data: EXAMPLE_PART_2,
+                part_1: None,
+                part_2: Some("23"),
+                params: &[],
+            },
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     #[test]
     fn test_day_22_mix() {
         assert_eq!(mix(42, 15), 37);
@@ -213,11 +262,14 @@ mod tests {
 
     #[test]
     fn test_day_22_part_1() {
-        assert_eq!(day_22_part_1(EXAMPLE_PART_1), 37327623);
+        assert_eq!(
+            day_22_part_1(&day_22_parse(EXAMPLE_PART_1).unwrap()),
+            37327623
+        );
     }
 
     #[test]
     fn test_day_22_part_2() {
-        assert_eq!(day_22_part_2(EXAMPLE_PART_2), 23);
+        assert_eq!(day_22_part_2(&day_22_parse(EXAMPLE_PART_2).unwrap()), 23);
     }
 }
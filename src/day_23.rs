@@ -37,9 +37,15 @@ use petgraph::{
 };
 use rayon::iter::{ParallelBridge, ParallelIterator};
 
+use crate::{
+    answer::Answer,
+    parsing::{parse_all, ParseError},
+    solution::{Example, Solution, SolveError},
+};
+
 #[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
-struct Identifier(char, char);
-struct Connection(Identifier, Identifier);
+pub struct Identifier(pub char, pub char);
+pub struct Connection(pub Identifier, pub Identifier);
 
 impl std::fmt::Display for Identifier {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -96,14 +102,17 @@ impl Identifier {
     }
 }
 
-fn build_graph(data: &[Connection]) -> UnGraph<(), ()> {
+/// The network of computers, a node index is the identifier as a number.
+pub fn build_graph(data: &[Connection]) -> UnGraph<(), ()> {
     UnGraph::<(), ()>::from_edges(data.iter().map(|c| (c.0.as_u32(), c.1.as_u32())))
 }
 
-pub fn day_23_part_1(data: &str) -> i64 {
-    let (_, data) = parse_input_data(data).expect("Failed to parse input data");
+pub fn day_23_parse(data: &str) -> Result<Vec<Connection>, ParseError> {
+    parse_all(23, data, parse_input_data)
+}
 
-    let graph = build_graph(&data);
+pub fn day_23_part_1(data: &[Connection]) -> i64 {
+    let graph = build_graph(data);
 
     graph
         .node_identifiers()
@@ -141,8 +150,8 @@ pub fn day_23_part_1(data: &str) -> i64 {
         .sum()
 }
 
-fn bron_kerbosch_with_pivot_recursive(
-    graph: &UnGraph<(), ()>,
+fn bron_kerbosch_with_pivot_recursive<N, E>(
+    graph: &UnGraph<N, E>,
     current_clique: &mut HashSet<NodeIndex>,
     candidate_nodes: HashSet<NodeIndex>,
     excluded_nodes: HashSet<NodeIndex>,
@@ -198,7 +207,8 @@ fn bron_kerbosch_with_pivot_recursive(
     }
 }
 
-fn find_all_maximal_cliques(graph: &UnGraph<(), ()>) -> Vec<HashSet<NodeIndex>> {
+/// All the maximal cliques of the graph, using Bron–Kerbosch with pivoting.
+pub fn find_all_maximal_cliques<N, E>(graph: &UnGraph<N, E>) -> Vec<HashSet<NodeIndex>> {
     let mut maximal_cliques: Vec<HashSet<NodeIndex>> = Vec::new();
     let mut current_clique = HashSet::new();
     let candidate_nodes: HashSet<NodeIndex> = graph.node_indices().collect();
@@ -215,7 +225,8 @@ fn find_all_maximal_cliques(graph: &UnGraph<(), ()>) -> Vec<HashSet<NodeIndex>>
     maximal_cliques
 }
 
-fn find_maximal_clique(graph: &UnGraph<(), ()>) -> Option<HashSet<NodeIndex>> {
+/// The largest clique of the graph, None when the graph is empty.
+pub fn find_maximal_clique<N, E>(graph: &UnGraph<N, E>) -> Option<HashSet<NodeIndex>> {
     let maximal_cliques = find_all_maximal_cliques(graph);
     maximal_cliques
         .iter()
@@ -223,26 +234,20 @@ fn find_maximal_clique(graph: &UnGraph<(), ()>) -> Option<HashSet<NodeIndex>> {
         .cloned()
 }
 
-pub fn day_23_part_2(data: &str) -> String {
-    let (_, data) = parse_input_data(data).expect("Failed to parse input data");
-    let graph = build_graph(&data);
+pub fn day_23_part_2(data: &[Connection]) -> Result<String, SolveError> {
+    let graph = build_graph(data);
 
-    let maximal_clique =
-        find_maximal_clique(&graph).expect("No maximal clique found, is the graph empty?");
+    let maximal_clique = find_maximal_clique(&graph).ok_or(SolveError::NoSolution)?;
 
-    maximal_clique
+    Ok(maximal_clique
         .iter()
         .map(|node| Identifier::from_usize(node.index()))
         .sorted_unstable()
         .map(|identifier| identifier.to_string())
-        .join(",")
+        .join(","))
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const EXAMPLE: &str = "kh-tc
+const EXAMPLE: &str = "kh-tc
 qp-kh
 de-cg
 ka-co
@@ -275,13 +280,55 @@ wh-qp
 tb-vc
 td-yn";
 
+pub struct Day23;
+
+impl Solution for Day23 {
+    type Input = Vec<Connection>;
+
+    fn day(&self) -> u8 {
+        23
+    }
+
+    fn name(&self) -> &'static str {
+        "LAN Party"
+    }
+
+    fn parse(&self, data: &str) -> Result<Self::Input, ParseError> {
+        day_23_parse(data)
+    }
+
+    fn part_1(&self, input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(day_23_part_1(input).into())
+    }
+
+    fn part_2(&self, input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(day_23_part_2(input)?.into())
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            data: EXAMPLE,
+            part_1: Some("7"),
+            part_2: Some("co,de,ka,ta"),
+            params: &[],
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     #[test]
     fn test_day_23_part_1() {
-        assert_eq!(day_23_part_1(EXAMPLE), 7);
+        assert_eq!(day_23_part_1(&day_23_parse(EXAMPLE).unwrap()), 7);
     }
 
     #[test]
     fn test_day_23_part_2() {
-        assert_eq!(day_23_part_2(EXAMPLE), "co,de,ka,ta");
+        assert_eq!(
+            day_23_part_2(&day_23_parse(EXAMPLE).unwrap()),
+            Ok("co,de,ka,ta".to_string())
+        );
     }
 }
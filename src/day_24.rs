@@ -3,22 +3,29 @@
     Day 23 being a relatively easy graph problem is also a clue that
     it may be a graph problem on Day 24 too.
 
-    I did implement a cache for the recursive function, but
-    I didn't want to serialise the whole graph so I wanted to use
-    its hash as a cache key, hoping for the best.
-    I thought that the defalut hash map implementation would be a bit
-    too straightforward, so I use Blake2 to hash into a 64-bit integer.
-
-    I don't like blake3 by the way, I rather be safe than sorry about
-    the number of rounds.
+    I first resolved the outputs with a recursive function and a global
+    cache, keyed by a Blake2 hash of the whole problem. The cache grew
+    forever and relied on the hashes never colliding. The gates are now
+    sorted in topological order once, which also finds the cycles, and
+    the circuit is evaluated in a single pass. It's cheap to evaluate
+    again with other x and y values, without any cache.
+
+    Part 2 is about finding the swapped wires of an adder. Trying swaps
+    and simulating is way too slow with more than 200 gates, so I check
+    each gate against the structure of a ripple-carry adder instead:
+
+    z_i = (x_i XOR y_i) XOR carry_i-1
+    carry_i = (x_i AND y_i) OR ((x_i XOR y_i) AND carry_i-1)
+
+    The first bit is a half adder, without the carry, and the last
+    output is the last carry. A gate that doesn't fit, like a z written
+    by something else than a XOR or an AND not going to the OR of a
+    carry, has its output swapped. It doesn't need to know which wire
+    it was swapped with.
 */
 
-use std::{
-    collections::{BTreeMap, HashMap},
-    hash::{Hash, Hasher},
-};
+use std::collections::{BTreeMap, HashMap};
 
-use cached::proc_macro::cached;
 use itertools::Itertools;
 use nom::{
     branch::alt,
@@ -29,7 +36,13 @@ use nom::{
     sequence::tuple,
     IResult,
 };
-use petgraph::{visit::IntoNodeReferences, Graph};
+use petgraph::{algo::toposort, visit::IntoNodeReferences, Direction::Incoming, Graph};
+
+use crate::{
+    answer::Answer,
+    parsing::{parse_all, ParseError},
+    solution::{Example, Solution, SolveError},
+};
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 struct WireName(u32);
@@ -81,6 +94,22 @@ impl WireName {
     fn is_output(&self) -> bool {
         self.0 / 1296 % 36 == 35
     }
+
+    fn prefix(&self) -> char {
+        wirename_u32_to_char(self.0 / 1296 % 36)
+    }
+
+    // True if starts by x or y
+    fn is_input(&self) -> bool {
+        matches!(self.prefix(), 'x' | 'y')
+    }
+
+    /// The bit of a numbered wire, like 5 for `x05`.
+    fn bit(&self) -> Option<usize> {
+        let tens = self.0 / 36 % 36;
+        let units = self.0 % 36;
+        (tens < 10 && units < 10).then_some((tens * 10 + units) as usize)
+    }
 }
 
 impl std::fmt::Display for WireName {
@@ -97,14 +126,14 @@ impl std::fmt::Debug for WireName {
     }
 }
 
-#[derive(Debug, Clone, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Operation {
     And,
     Or,
     Xor,
 }
 
-#[derive(Clone, Hash)]
+#[derive(Clone)]
 struct Gate {
     input_a: WireName,
     input_b: WireName,
@@ -112,6 +141,26 @@ struct Gate {
     operation: Operation,
 }
 
+impl std::fmt::Display for Operation {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Operation::And => write!(f, "AND"),
+            Operation::Or => write!(f, "OR"),
+            Operation::Xor => write!(f, "XOR"),
+        }
+    }
+}
+
+impl std::fmt::Display for Gate {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} {} -> {}",
+            self.input_a, self.operation, self.input_b, self.output
+        )
+    }
+}
+
 impl std::fmt::Debug for Gate {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(
@@ -162,8 +211,8 @@ fn parse_gate(input: &str) -> IResult<&str, Gate> {
     )(input)
 }
 
-#[derive(Debug, Hash)]
-struct Problem {
+#[derive(Debug)]
+pub struct Problem {
     gates: Vec<Gate>,
     initial_values: BTreeMap<WireName, bool>,
 }
@@ -248,7 +297,7 @@ impl std::fmt::Debug for GraphNode {
     }
 }
 
-fn build_graph(data: &Problem) -> Graph<GraphNode, ()> {
+fn build_graph(data: &Problem) -> Result<Graph<GraphNode, ()>, SolveError> {
     let mut graph = Graph::<GraphNode, ()>::new();
 
     let mut map: HashMap<WireName, petgraph::graph::NodeIndex> = HashMap::new();
@@ -266,14 +315,24 @@ fn build_graph(data: &Problem) -> Graph<GraphNode, ()> {
     // before we add the edges.
     for gate in &data.gates {
         let node_index = graph.add_node(GraphNode::Gate(gate.clone()));
-        map.insert(gate.output, node_index);
+        if map.insert(gate.output, node_index).is_some() {
+            return Err(SolveError::InvalidGate(format!(
+                "{}, the wire {} already has a value",
+                gate, gate.output
+            )));
+        }
     }
 
     // Add the edges
     for gate in &data.gates {
         let node_index_gate = map[&gate.output];
-        let node_index_input_a = map[&gate.input_a];
-        let node_index_input_b = map[&gate.input_b];
+        let wire_node = |wire: &WireName| {
+            map.get(wire).copied().ok_or_else(|| {
+                SolveError::InvalidGate(format!("{}, the wire {} has no value", gate, wire))
+            })
+        };
+        let node_index_input_a = wire_node(&gate.input_a)?;
+        let node_index_input_b = wire_node(&gate.input_b)?;
 
         //graph.add_edge(node_index_input_a, node_index_gate, ());
         //graph.add_edge(node_index_input_b, node_index_gate, ());
@@ -294,118 +353,533 @@ fn build_graph(data: &Problem) -> Graph<GraphNode, ()> {
     //graph.add_edge(gate.input_b, gate.output, gate.operation);
     //}
 
-    graph
+    Ok(graph)
 }
 
-#[cached(
-    key = "(u64, petgraph::graph::NodeIndex)",
-    convert = "{ (graph_hash, node_index.clone()) }"
-)]
-fn resolve_node_recursive(
+/// The nodes of the graph, each one after the nodes it reads, so the
+/// inputs come first. A gate that depends on its own output is an error.
+fn sorted_nodes(
     graph: &Graph<GraphNode, ()>,
-    graph_hash: u64,
-    node_index: petgraph::graph::NodeIndex,
-) -> bool {
-    let node_weight = graph.node_weight(node_index).expect("Node not found");
-
-    match node_weight {
-        GraphNode::Input(input) => input.value,
+) -> Result<Vec<petgraph::graph::NodeIndex>, SolveError> {
+    let mut order = toposort(graph, None).map_err(|cycle| match &graph[cycle.node_id()] {
         GraphNode::Gate(gate) => {
-            let neighbors = graph.neighbors(node_index).collect_tuple();
-            if let Some((input_a, input_b)) = neighbors {
-                let input_a = resolve_node_recursive(graph, graph_hash, input_a);
-                let input_b = resolve_node_recursive(graph, graph_hash, input_b);
-                match gate.operation {
-                    Operation::And => input_a && input_b,
-                    Operation::Or => input_a || input_b,
-                    Operation::Xor => input_a ^ input_b,
+            SolveError::InvalidGate(format!("{}, the gate depends on its own output", gate))
+        }
+        GraphNode::Input(input) => SolveError::InvalidGate(input.wire.to_string()),
+    })?;
+    // The edges go from the gates to their inputs
+    order.reverse();
+    Ok(order)
+}
+
+/// A gate of the circuit, the wires are the indexes of their nodes.
+#[derive(Debug, Clone, Copy)]
+struct CircuitGate {
+    operation: Operation,
+    input_a: usize,
+    input_b: usize,
+    output: usize,
+}
+
+/// The circuit sorted once in topological order, each gate after the
+/// gates writing its inputs, so it's evaluated in a single pass. The x
+/// and y inputs can be changed at each evaluation.
+#[derive(Debug, Clone)]
+pub struct Circuit {
+    gates: Vec<CircuitGate>,
+    /// The value of each wire before the evaluation, from the input.
+    initial_values: Vec<bool>,
+    /// The bit and the wire of each x input.
+    x: Vec<(usize, usize)>,
+    /// The bit and the wire of each y input.
+    y: Vec<(usize, usize)>,
+    /// The z outputs, from the lowest to the highest bit.
+    outputs: Vec<usize>,
+}
+
+impl Circuit {
+    pub fn new(data: &Problem) -> Result<Circuit, SolveError> {
+        let graph = build_graph(data)?;
+
+        let mut gates = Vec::with_capacity(data.gates.len());
+        let mut initial_values = vec![false; graph.node_count()];
+        let mut x = Vec::new();
+        let mut y = Vec::new();
+        let mut outputs = Vec::new();
+        for node in sorted_nodes(&graph)? {
+            match &graph[node] {
+                GraphNode::Input(input) => {
+                    initial_values[node.index()] = input.value;
+                    match (input.wire.prefix(), input.wire.bit()) {
+                        ('x', Some(bit)) => x.push((bit, node.index())),
+                        ('y', Some(bit)) => y.push((bit, node.index())),
+                        _ => {}
+                    }
                 }
-            } else {
-                panic!("Invalid gate");
+                GraphNode::Gate(gate) => {
+                    let Some((input_a, input_b)) = graph.neighbors(node).collect_tuple() else {
+                        return Err(SolveError::InvalidGate(gate.to_string()));
+                    };
+                    gates.push(CircuitGate {
+                        operation: gate.operation,
+                        input_a: input_a.index(),
+                        input_b: input_b.index(),
+                        output: node.index(),
+                    });
+                    if gate.output.is_output() {
+                        outputs.push((gate.output, node.index()));
+                    }
+                }
+            }
+        }
+
+        if outputs.len() > 64 {
+            return Err(SolveError::Unsupported(format!(
+                "{} output wires don't fit in a 64-bit number",
+                outputs.len()
+            )));
+        }
+        outputs.sort_unstable();
+        Ok(Circuit {
+            gates,
+            initial_values,
+            x,
+            y,
+            outputs: outputs.into_iter().map(|(_, node)| node).collect(),
+        })
+    }
+
+    /// The number on the z outputs, with the values of the input.
+    pub fn evaluate(&self) -> u64 {
+        self.run(self.initial_values.clone())
+    }
+
+    /// The number on the z outputs, with the x and y inputs set to the bits
+    /// of `x` and `y`. The bits without a wire are ignored.
+    pub fn evaluate_with(&self, x: u64, y: u64) -> u64 {
+        let mut values = self.initial_values.clone();
+        for (wires, number) in [(&self.x, x), (&self.y, y)] {
+            for &(bit, wire) in wires {
+                values[wire] = number.checked_shr(bit as u32).unwrap_or(0) & 1 == 1;
             }
-            /*let input_a = resolve_node_recursive(graph, node_index);
-            let input_b = resolve_node_recursive(graph, node_index);
-            match gate.operation {
+        }
+        self.run(values)
+    }
+
+    fn run(&self, mut values: Vec<bool>) -> u64 {
+        for gate in &self.gates {
+            let input_a = values[gate.input_a];
+            let input_b = values[gate.input_b];
+            values[gate.output] = match gate.operation {
                 Operation::And => input_a && input_b,
                 Operation::Or => input_a || input_b,
                 Operation::Xor => input_a ^ input_b,
-            }*/
+            };
+        }
+        // Build back the binary number
+        self.outputs
+            .iter()
+            .rev()
+            .fold(0, |acc, &wire| acc << 1 | values[wire] as u64)
+    }
+
+    /// The number of bits of the x and y inputs.
+    pub fn input_bits(&self) -> usize {
+        self.x
+            .iter()
+            .chain(&self.y)
+            .map(|(bit, _)| bit + 1)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// The x and y numbers of the input.
+    pub fn inputs(&self) -> (u64, u64) {
+        let number = |wires: &[(usize, usize)]| {
+            wires
+                .iter()
+                .filter(|(bit, wire)| *bit < 64 && self.initial_values[*wire])
+                .fold(0, |acc, (bit, _)| acc | 1 << bit)
+        };
+        (number(&self.x), number(&self.y))
+    }
+
+    /// The bits that fit in the x and y inputs.
+    fn input_mask(&self) -> u64 {
+        1u64.checked_shl(self.input_bits() as u32)
+            .map_or(u64::MAX, |bit| bit - 1)
+    }
+
+    /// Adds `x` and `y` with the circuit, as long as they fit in its inputs.
+    pub fn add(&self, x: u64, y: u64) -> Result<Addition, SolveError> {
+        let mask = self.input_mask();
+        if let Some(number) = [x, y].into_iter().find(|number| number & !mask != 0) {
+            return Err(SolveError::Unsupported(format!(
+                "{} is wider than the {} bits of the circuit inputs",
+                number,
+                self.input_bits()
+            )));
         }
+        Ok(Addition {
+            x,
+            y,
+            z: self.evaluate_with(x, y),
+        })
+    }
+
+    /// `count` additions of random numbers of the size of the inputs, the
+    /// same ones for the same `seed`.
+    pub fn random_additions(&self, count: usize, seed: u64) -> Vec<Addition> {
+        // SplitMix64, good enough to pick numbers and no crate needed
+        let mut state = seed;
+        let mut next = || {
+            state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+            let mut z = state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+            z ^ (z >> 31)
+        };
+        let mask = self.input_mask();
+        (0..count)
+            .map(|_| {
+                let (x, y) = (next() & mask, next() & mask);
+                Addition {
+                    x,
+                    y,
+                    z: self.evaluate_with(x, y),
+                }
+            })
+            .collect()
     }
 }
 
-/* somewhat over-engineered */
-fn get_problem_hash(data: &Problem) -> u64 {
-    use blake2::Blake2s256;
-    use blake2::Digest;
+/// An addition done by the circuit, with what it wrote on the outputs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Addition {
+    pub x: u64,
+    pub y: u64,
+    pub z: u64,
+}
 
-    struct Blake2Hasher(blake2::Blake2s256);
+impl Addition {
+    pub fn expected(&self) -> u64 {
+        self.x.wrapping_add(self.y)
+    }
 
-    impl Blake2Hasher {
-        fn new() -> Self {
-            Self(Blake2s256::new())
+    /// The lowest bit of the output that differs from the sum, `None` when
+    /// the circuit added right.
+    pub fn lowest_failing_bit(&self) -> Option<usize> {
+        let wrong = self.z ^ self.expected();
+        (wrong != 0).then(|| wrong.trailing_zeros() as usize)
+    }
+}
+
+impl std::fmt::Display for Addition {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{} + {} = {}", self.x, self.y, self.z)?;
+        if let Some(bit) = self.lowest_failing_bit() {
+            write!(f, ", expected {}, wrong from bit {}", self.expected(), bit)?;
         }
+        Ok(())
     }
+}
+
+pub fn day_24_parse(data: &str) -> Result<Problem, ParseError> {
+    parse_all(24, data, parse_input_data)
+}
+
+pub fn day_24_part_1(data: &Problem) -> Result<i64, SolveError> {
+    let number = Circuit::new(data)?.evaluate();
+    i64::try_from(number).map_err(|_| {
+        SolveError::Unsupported(format!("{} doesn't fit in a signed 64-bit number", number))
+    })
+}
+
+/// The number of bits of the numbers added by the circuit, as long as
+/// it has the `x`, `y` and `z` wires of an adder.
+fn adder_bits(data: &Problem) -> Result<usize, SolveError> {
+    // The number of wires with the prefix, if they are numbered from 0
+    fn count(wires: impl Iterator<Item = WireName>, prefix: char) -> Option<usize> {
+        let mut bits = wires
+            .filter(|wire| wire.prefix() == prefix)
+            .map(|wire| wire.bit())
+            .collect::<Option<Vec<_>>>()?;
+        bits.sort_unstable();
+        bits.dedup();
+        bits.iter()
+            .enumerate()
+            .all(|(index, bit)| index == *bit)
+            .then_some(bits.len())
+    }
+    let x = count(data.initial_values.keys().copied(), 'x');
+    let y = count(data.initial_values.keys().copied(), 'y');
+    let z = count(data.gates.iter().map(|gate| gate.output), 'z');
+    match (x, y, z) {
+        (Some(x), Some(y), Some(z)) if x >= 2 && x == y && z == x + 1 => Ok(x),
+        _ => Err(SolveError::Unsupported(
+            "the circuit isn't an adder, it needs x and y wires of the same \
+             number of bits and one more z wire"
+                .to_string(),
+        )),
+    }
+}
+
+/// The bit of the adder each node is part of. The bit of the x and y
+/// inputs, or of the z output, and otherwise the highest bit of its
+/// inputs, so the carry of a bit stays with that bit.
+fn node_bits(
+    graph: &Graph<GraphNode, ()>,
+) -> Result<HashMap<petgraph::graph::NodeIndex, usize>, SolveError> {
+    let mut bits = HashMap::new();
+    for node in sorted_nodes(graph)? {
+        let bit = match &graph[node] {
+            GraphNode::Input(input) => input.wire.bit().unwrap_or(0),
+            GraphNode::Gate(gate) => {
+                let inputs = graph
+                    .neighbors(node)
+                    .map(|input| bits[&input])
+                    .max()
+                    .unwrap_or(0);
+                let from_inputs = gate.input_a.is_input() && gate.input_b.is_input();
+                match gate.output.bit() {
+                    Some(bit) if gate.output.is_output() && !from_inputs => bit,
+                    _ => inputs,
+                }
+            }
+        };
+        bits.insert(node, bit);
+    }
+    Ok(bits)
+}
 
-    impl Hasher for Blake2Hasher {
-        fn write(&mut self, bytes: &[u8]) {
-            self.0.update(bytes);
+/// Why the gate doesn't fit in a ripple-carry adder of `bits` bits, given
+/// the operations of the gates reading its output.
+fn adder_violation(gate: &Gate, bits: usize, consumers: &[Operation]) -> Option<&'static str> {
+    let from_inputs = gate.input_a.is_input() && gate.input_b.is_input();
+    // The half adder of the first bit has no carry to add
+    let first = from_inputs && gate.input_a.bit() == Some(0);
+
+    if gate.output.is_output() {
+        if gate.output.bit() == Some(bits) {
+            if gate.operation != Operation::Or {
+                return Some("writes the last output bit, the last carry, but isn't an OR");
+            }
+            return None;
+        }
+        if gate.operation != Operation::Xor {
+            return Some("writes an output bit but isn't a XOR");
         }
+    }
 
-        fn finish(&self) -> u64 {
-            let result = self.0.clone().finalize();
-            let mut output = [0u8; 8];
-            output.copy_from_slice(&result[..8]); // Take the first 8 bytes
-            u64::from_be_bytes(output)
+    match gate.operation {
+        Operation::Xor if !from_inputs && !gate.output.is_output() => {
+            Some("adds the carry but doesn't write an output bit")
         }
+        Operation::Xor if from_inputs && !first && !consumers.contains(&Operation::Xor) => {
+            Some("adds the input bits but doesn't go to the XOR with the carry")
+        }
+        Operation::And if !first && !consumers.contains(&Operation::Or) => {
+            Some("is part of a carry but doesn't go to the OR of the carry")
+        }
+        _ => None,
     }
+}
 
-    use std::hash::Hash;
-    let mut hasher = Blake2Hasher::new();
-    data.hash(&mut hasher);
-    hasher.finish()
+/// A gate that doesn't fit in a ripple-carry adder, its output is
+/// probably one of the swapped wires.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdderViolation {
+    /// The bit of the adder the gate is part of.
+    pub bit: usize,
+    pub gate: String,
+    pub wire: String,
+    pub reason: &'static str,
 }
 
-pub fn day_24_part_1(data: &str) -> i64 {
-    let (_, data) = parse_input_data(data).expect("Failed to parse input data");
+impl std::fmt::Display for AdderViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}: {}", self.gate, self.reason)
+    }
+}
+
+/// The gates of the circuit that don't fit in a ripple-carry adder,
+/// sorted by bit.
+pub fn adder_violations(data: &Problem) -> Result<Vec<AdderViolation>, SolveError> {
+    let bits = adder_bits(data)?;
+    let graph = build_graph(data)?;
+    let node_bits = node_bits(&graph)?;
+
+    let mut violations = Vec::new();
+    for (node, weight) in graph.node_references() {
+        let GraphNode::Gate(gate) = weight else {
+            continue;
+        };
+        let consumers = graph
+            .neighbors_directed(node, Incoming)
+            .filter_map(|consumer| match &graph[consumer] {
+                GraphNode::Gate(consumer) => Some(consumer.operation),
+                GraphNode::Input(_) => None,
+            })
+            .collect::<Vec<_>>();
+        if let Some(reason) = adder_violation(gate, bits, &consumers) {
+            violations.push((
+                node_bits[&node],
+                gate.output,
+                AdderViolation {
+                    bit: node_bits[&node],
+                    gate: gate.to_string(),
+                    wire: gate.output.to_string(),
+                    reason,
+                },
+            ));
+        }
+    }
+    violations.sort_unstable_by_key(|(bit, wire, _)| (*bit, *wire));
+    Ok(violations
+        .into_iter()
+        .map(|(_, _, violation)| violation)
+        .collect())
+}
 
-    // We need a good hash to use as a cache key
-    let hash = get_problem_hash(&data);
+/// The gates that don't fit in the adder, grouped by bit, a line per gate.
+pub fn adder_report(data: &Problem) -> Result<String, SolveError> {
+    let mut report = String::new();
+    let mut previous_bit = None;
+    for violation in adder_violations(data)? {
+        if previous_bit != Some(violation.bit) {
+            report.push_str(&format!("bit {:02}\n", violation.bit));
+            previous_bit = Some(violation.bit);
+        }
+        report.push_str(&format!("  {}\n", violation));
+    }
+    Ok(report)
+}
 
-    let graph = build_graph(&data);
+impl Operation {
+    /// The shape and the fill colour of the gate in the DOT export.
+    fn dot_style(&self) -> (&'static str, &'static str) {
+        match self {
+            Operation::And => ("box", "lightblue"),
+            Operation::Or => ("ellipse", "palegreen"),
+            Operation::Xor => ("diamond", "khaki"),
+        }
+    }
+}
 
-    // get all the output nodes first before sorting them
-    let mut outputs = graph
-        .node_references()
-        .filter(|(_, weight)| match &weight {
-            GraphNode::Gate(gate) => gate.output.is_output(),
-            _ => false,
-        })
+/// The circuit in the DOT language of Graphviz, to look at its structure.
+///
+/// A node per wire: the inputs, and the gates named after the wire they
+/// write. The x, y and z wires of each bit share a rank, so the bits
+/// line up. The `highlighted` wires, like the ones of part 2, are drawn
+/// in red with the wires they go to.
+pub fn circuit_dot(data: &Problem, highlighted: &[String]) -> Result<String, SolveError> {
+    let graph = build_graph(data)?;
+    let wire = |node: petgraph::graph::NodeIndex| match &graph[node] {
+        GraphNode::Gate(gate) => gate.output,
+        GraphNode::Input(input) => input.wire,
+    };
+    let wires = graph
+        .node_indices()
+        .map(|node| wire(node).to_string())
         .collect::<Vec<_>>();
+    if let Some(unknown) = highlighted.iter().find(|name| !wires.contains(name)) {
+        return Err(SolveError::InvalidParameter(format!(
+            "`{}`, there is no such wire in the circuit",
+            unknown
+        )));
+    }
+    let is_highlighted = |node| highlighted.contains(&wire(node).to_string());
+    const HIGHLIGHT: &str = ", color=red, penwidth=3";
+
+    let mut dot = String::from("digraph circuit {\n    rankdir=LR;\n");
+    dot.push_str("    node [fontname=\"monospace\", style=filled, fillcolor=white];\n");
+
+    // Quoted, as a wire starting with a digit like 3ab isn't a DOT id otherwise
+    let id = |wire: WireName| format!("\"{}\"", wire);
+
+    let mut bits: BTreeMap<usize, Vec<WireName>> = BTreeMap::new();
+    for (node, weight) in graph.node_references() {
+        let style = if is_highlighted(node) { HIGHLIGHT } else { "" };
+        match weight {
+            GraphNode::Input(input) => {
+                if let (true, Some(bit)) = (input.wire.is_input(), input.wire.bit()) {
+                    bits.entry(bit).or_default().push(input.wire);
+                }
+                dot.push_str(&format!(
+                    "    {} [shape=circle, label=\"{}\\n{}\"{}];\n",
+                    id(input.wire),
+                    input.wire,
+                    input.value as u8,
+                    style
+                ));
+            }
+            GraphNode::Gate(gate) => {
+                if let (true, Some(bit)) = (gate.output.is_output(), gate.output.bit()) {
+                    bits.entry(bit).or_default().push(gate.output);
+                }
+                let (shape, colour) = gate.operation.dot_style();
+                dot.push_str(&format!(
+                    "    {} [shape={}, fillcolor={}, label=\"{}\\n{}\"{}];\n",
+                    id(gate.output),
+                    shape,
+                    colour,
+                    gate.operation,
+                    gate.output,
+                    style
+                ));
+            }
+        }
+    }
 
-    outputs.sort_unstable_by_key(|(_, weight)| match weight {
-        GraphNode::Gate(gate) => std::cmp::Reverse(gate.output),
-        _ => panic!("Invalid node"),
-    });
-
-    outputs
-        .iter()
-        .map(|(node, _)| resolve_node_recursive(&graph, hash, *node))
-        // Build back the binary number
-        .fold(0, |acc, value| acc << 1 | value as i64)
-}
+    // The edges of the graph go from the gates to their inputs
+    for edge in graph.raw_edges() {
+        let style = if is_highlighted(edge.target()) {
+            " [color=red, penwidth=3]"
+        } else {
+            ""
+        };
+        dot.push_str(&format!(
+            "    {} -> {}{};\n",
+            id(wire(edge.target())),
+            id(wire(edge.source())),
+            style
+        ));
+    }
 
-pub fn day_24_part_2(data: &str) -> i64 {
-    42
+    for mut wires in bits.into_values() {
+        wires.sort_unstable();
+        dot.push_str(&format!(
+            "    {{ rank=same; {}; }}\n",
+            wires.into_iter().map(id).join("; ")
+        ));
+    }
+    dot.push_str("}\n");
+    Ok(dot)
 }
 
-#[cfg(test)]
-mod tests {
-    use std::collections::HashSet;
+/// The puzzle swaps the outputs of four pairs of gates.
+const SWAPPED_WIRES: usize = 8;
 
-    use super::*;
+pub fn day_24_part_2(data: &Problem) -> Result<String, SolveError> {
+    let mut wires = adder_violations(data)?
+        .into_iter()
+        .map(|violation| violation.wire)
+        .collect::<Vec<_>>();
+    wires.sort_unstable();
+    wires.dedup();
+    // The violations are a heuristic, a wrong count is a wrong answer
+    if wires.len() != SWAPPED_WIRES {
+        return Err(SolveError::Unsupported(format!(
+            "expected {} swapped wires, the adder analysis found {}\n{}",
+            SWAPPED_WIRES,
+            wires.len(),
+            adder_report(data)?
+        )));
+    }
+    Ok(wires.join(","))
+}
 
-    const EXAMPLE_SHORT: &str = "x00: 1
+const EXAMPLE_SHORT: &str = "x00: 1
 x01: 1
 x02: 1
 y00: 0
@@ -416,7 +890,7 @@ x00 AND y00 -> z00
 x01 XOR y01 -> z01
 x02 OR y02 -> z02";
 
-    const EXAMPLE_LONG: &str = "x00: 1
+const EXAMPLE_LONG: &str = "x00: 1
 x01: 0
 x02: 1
 x03: 1
@@ -464,6 +938,55 @@ hwm AND bqk -> z03
 tgd XOR rvg -> z12
 tnw OR pbm -> gnj";
 
+pub struct Day24;
+
+impl Solution for Day24 {
+    type Input = Problem;
+
+    fn day(&self) -> u8 {
+        24
+    }
+
+    fn name(&self) -> &'static str {
+        "Crossed Wires"
+    }
+
+    fn parse(&self, data: &str) -> Result<Self::Input, ParseError> {
+        day_24_parse(data)
+    }
+
+    fn part_1(&self, input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(day_24_part_1(input)?.into())
+    }
+
+    fn part_2(&self, input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(day_24_part_2(input)?.into())
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                data: EXAMPLE_SHORT,
+                part_1: Some("4"),
+                part_2: None,
+                params: &[],
+            },
+            Example {
+                data: EXAMPLE_LONG,
+                part_1: Some("2024"),
+                part_2: None,
+                params: &[],
+            },
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
     // Just a quick test as I don't trust myself enough :D
     #[test]
     fn test_day_24_all_unique_wire_names() {
@@ -507,12 +1030,245 @@ tnw OR pbm -> gnj";
 
     #[test]
     fn test_day_24_part_1() {
-        assert_eq!(day_24_part_1(EXAMPLE_SHORT), 4);
-        assert_eq!(day_24_part_1(EXAMPLE_LONG), 2024);
+        assert_eq!(day_24_part_1(&day_24_parse(EXAMPLE_SHORT).unwrap()), Ok(4));
+        assert_eq!(
+            day_24_part_1(&day_24_parse(EXAMPLE_LONG).unwrap()),
+            Ok(2024)
+        );
+        let problem = day_24_parse("x00: 1\n\nx00 AND y00 -> z00").unwrap();
+        assert_eq!(
+            day_24_part_1(&problem),
+            Err(SolveError::InvalidGate(
+                "x00 AND y00 -> z00, the wire y00 has no value".to_string()
+            ))
+        );
+    }
+
+    /// A ripple-carry adder of `bits` bits, with the outputs of the
+    /// gates swapped by pairs. The sums are `sNN`, the carries `cNN`, and
+    /// the two parts of the carry `aNN` and `tNN`.
+    fn ripple_carry_adder(bits: usize, x: u64, y: u64, swaps: &[(&str, &str)]) -> String {
+        let mut lines = Vec::new();
+        for (name, value) in [("x", x), ("y", y)] {
+            for bit in 0..bits {
+                lines.push(format!("{}{:02}: {}", name, bit, value >> bit & 1));
+            }
+        }
+        lines.push(String::new());
+
+        let mut gates = vec![
+            ("x00 XOR y00".to_string(), "z00".to_string()),
+            ("x00 AND y00".to_string(), "c00".to_string()),
+        ];
+        for bit in 1..bits {
+            let carry = if bit == bits - 1 {
+                format!("z{:02}", bits)
+            } else {
+                format!("c{:02}", bit)
+            };
+            gates.extend([
+                (format!("x{0:02} XOR y{0:02}", bit), format!("s{:02}", bit)),
+                (format!("x{0:02} AND y{0:02}", bit), format!("a{:02}", bit)),
+                (
+                    format!("s{0:02} XOR c{1:02}", bit, bit - 1),
+                    format!("z{:02}", bit),
+                ),
+                (
+                    format!("s{0:02} AND c{1:02}", bit, bit - 1),
+                    format!("t{:02}", bit),
+                ),
+                (format!("a{0:02} OR t{0:02}", bit), carry),
+            ]);
+        }
+        for (inputs, output) in gates {
+            let output = swaps
+                .iter()
+                .find_map(|(a, b)| match output.as_str() {
+                    wire if wire == *a => Some(b.to_string()),
+                    wire if wire == *b => Some(a.to_string()),
+                    _ => None,
+                })
+                .unwrap_or(output);
+            lines.push(format!("{} -> {}", inputs, output));
+        }
+        lines.join("\n")
+    }
+
+    const SWAPS: [(&str, &str); 4] = [
+        ("z07", "t07"),
+        ("s10", "a10"),
+        ("z15", "c15"),
+        ("z30", "a30"),
+    ];
+
+    #[test]
+    fn test_day_24_circuit() {
+        let circuit = Circuit::new(&day_24_parse(EXAMPLE_LONG).unwrap()).unwrap();
+        assert_eq!(circuit.evaluate(), 2024);
+        // Evaluating with other inputs doesn't change the next evaluations
+        assert_ne!(circuit.evaluate_with(0, 0), 2024);
+        assert_eq!(circuit.evaluate(), 2024);
+
+        let adder = day_24_parse(&ripple_carry_adder(45, 0, 0, &[])).unwrap();
+        let circuit = Circuit::new(&adder).unwrap();
+        for (x, y) in [(0, 0), (1, 1), (12345, 67890), ((1 << 45) - 1, 1)] {
+            assert_eq!(circuit.evaluate_with(x, y), x + y);
+        }
+
+        let cycle = day_24_parse("x00: 1\n\nx00 AND abc -> def\nx00 OR def -> abc").unwrap();
+        assert!(matches!(
+            Circuit::new(&cycle),
+            Err(SolveError::InvalidGate(gate)) if gate.ends_with("depends on its own output")
+        ));
+    }
+
+    #[test]
+    fn test_day_24_additions() {
+        let circuit = Circuit::new(&day_24_parse(EXAMPLE_LONG).unwrap()).unwrap();
+        assert_eq!(circuit.input_bits(), 5);
+        assert_eq!(circuit.inputs(), (0b01101, 0b11111));
+
+        let adder = day_24_parse(&ripple_carry_adder(45, 12345, 67890, &[])).unwrap();
+        let circuit = Circuit::new(&adder).unwrap();
+        assert_eq!(circuit.inputs(), (12345, 67890));
+        assert!(circuit
+            .random_additions(100, 42)
+            .iter()
+            .all(|addition| addition.lowest_failing_bit().is_none()));
+        assert_eq!(circuit.add(1, 2), Ok(Addition { x: 1, y: 2, z: 3 }));
+        // The numbers must fit in the 45 bits of the inputs
+        assert!(matches!(
+            circuit.add(1 << 45 | 1, 2),
+            Err(SolveError::Unsupported(_))
+        ));
+
+        // 64 outputs fit in the sum, but not always in part 1's signed answer
+        let adder = day_24_parse(&ripple_carry_adder(63, 1 << 61, 1 << 61, &[])).unwrap();
+        assert_eq!(day_24_part_1(&adder), Ok(1 << 62));
+        let adder = day_24_parse(&ripple_carry_adder(63, 1 << 62, 1 << 62, &[])).unwrap();
+        assert_eq!(Circuit::new(&adder).unwrap().evaluate(), 1 << 63);
+        assert!(matches!(
+            day_24_part_1(&adder),
+            Err(SolveError::Unsupported(_))
+        ));
+
+        let swapped = day_24_parse(&ripple_carry_adder(45, 0, 0, &SWAPS[..1])).unwrap();
+        let circuit = Circuit::new(&swapped).unwrap();
+        let addition = circuit.add(1 << 7, 0).unwrap();
+        assert_eq!(addition.lowest_failing_bit(), Some(7));
+        assert_eq!(
+            addition.to_string(),
+            "128 + 0 = 256, expected 128, wrong from bit 7"
+        );
+        assert_eq!(
+            circuit
+                .random_additions(100, 42)
+                .iter()
+                .filter_map(Addition::lowest_failing_bit)
+                .min(),
+            Some(7)
+        );
+        assert_eq!(
+            circuit.random_additions(3, 1),
+            circuit.random_additions(3, 1)
+        );
     }
 
     #[test]
     fn test_day_24_part_2() {
-        assert_eq!(day_24_part_2(EXAMPLE_SHORT), 42);
+        let adder = day_24_parse(&ripple_carry_adder(45, 0, 0, &[])).unwrap();
+        assert_eq!(
+            day_24_part_2(&adder),
+            Err(SolveError::Unsupported(
+                "expected 8 swapped wires, the adder analysis found 0\n".to_string()
+            ))
+        );
+        let adder = day_24_parse(&ripple_carry_adder(45, 12345, 67890, &[])).unwrap();
+        assert_eq!(day_24_part_1(&adder), Ok(12345 + 67890));
+
+        let swapped = day_24_parse(&ripple_carry_adder(45, 0, 0, &SWAPS)).unwrap();
+        assert_eq!(
+            day_24_part_2(&swapped),
+            Ok("a10,a30,c15,s10,t07,z07,z15,z30".to_string())
+        );
+        let swapped = day_24_parse(&ripple_carry_adder(45, 0, 0, &SWAPS[..1])).unwrap();
+        let report = adder_report(&swapped).unwrap();
+        assert!(report.starts_with("bit 07\n"));
+        assert_eq!(
+            day_24_part_2(&swapped),
+            Err(SolveError::Unsupported(format!(
+                "expected 8 swapped wires, the adder analysis found 2\n{}",
+                report
+            )))
+        );
+
+        assert!(matches!(
+            day_24_part_2(&day_24_parse(EXAMPLE_SHORT).unwrap()),
+            Err(SolveError::Unsupported(_))
+        ));
+    }
+
+    #[test]
+    fn test_day_24_adder_report() {
+        let swapped = day_24_parse(&ripple_carry_adder(45, 0, 0, &SWAPS[..2])).unwrap();
+        assert_eq!(
+            adder_report(&swapped),
+            Ok("bit 07
+  s07 XOR c06 -> t07: adds the carry but doesn't write an output bit
+  s07 AND c06 -> z07: writes an output bit but isn't a XOR
+bit 10
+  x10 XOR y10 -> a10: adds the input bits but doesn't go to the XOR with the carry
+  x10 AND y10 -> s10: is part of a carry but doesn't go to the OR of the carry
+"
+            .to_string())
+        );
+        let violations = adder_violations(&swapped).unwrap();
+        assert_eq!(violations[1].bit, 7);
+        assert_eq!(violations[1].wire, "z07");
+    }
+
+    #[test]
+    fn test_day_24_circuit_dot() {
+        let problem = day_24_parse(EXAMPLE_SHORT).unwrap();
+        assert_eq!(
+            circuit_dot(&problem, &["z01".to_string()]),
+            Ok(r#"digraph circuit {
+    rankdir=LR;
+    node [fontname="monospace", style=filled, fillcolor=white];
+    "x00" [shape=circle, label="x00\n1"];
+    "x01" [shape=circle, label="x01\n1"];
+    "x02" [shape=circle, label="x02\n1"];
+    "y00" [shape=circle, label="y00\n0"];
+    "y01" [shape=circle, label="y01\n1"];
+    "y02" [shape=circle, label="y02\n0"];
+    "z00" [shape=box, fillcolor=lightblue, label="AND\nz00"];
+    "z01" [shape=diamond, fillcolor=khaki, label="XOR\nz01", color=red, penwidth=3];
+    "z02" [shape=ellipse, fillcolor=palegreen, label="OR\nz02"];
+    "x00" -> "z00";
+    "y00" -> "z00";
+    "x01" -> "z01";
+    "y01" -> "z01";
+    "x02" -> "z02";
+    "y02" -> "z02";
+    { rank=same; "x00"; "y00"; "z00"; }
+    { rank=same; "x01"; "y01"; "z01"; }
+    { rank=same; "x02"; "y02"; "z02"; }
+}
+"#
+            .to_string())
+        );
+
+        let swapped = day_24_parse(&ripple_carry_adder(4, 0, 0, &[("z02", "t02")])).unwrap();
+        let dot = circuit_dot(&swapped, &["z02".to_string(), "t02".to_string()]).unwrap();
+        assert!(dot.contains("    \"t02\" -> \"c02\" [color=red, penwidth=3];\n"));
+        assert!(dot.contains("    \"c01\" -> \"z02\";\n"));
+        // The last carry is z04, alone on its rank
+        assert!(dot.contains("    { rank=same; \"x03\"; \"y03\"; \"z03\"; }\n"));
+        assert!(dot.contains("    { rank=same; \"z04\"; }\n"));
+
+        assert!(matches!(
+            circuit_dot(&problem, &["abc".to_string()]),
+            Err(SolveError::InvalidParameter(_))
+        ));
     }
 }
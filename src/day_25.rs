@@ -1,130 +1,271 @@
 /*
-    Comments.
+    The last day is short. A schematic is a lock when its top row is
+    full and a key when its bottom row is, the heights of the columns
+    are what matters. A key fits a lock when no column overlaps, so
+    when the heights of each column add up to the space between the
+    top and the bottom rows at most.
+
+    I first parsed the schematics as exactly 5 columns and 7 rows, with
+    nom checking the characters and nothing else. Anything else, like a
+    pin with a hole in it, was read as something it wasn't. The rows are
+    now read first and checked afterwards, any size works as long as
+    all the schematics have the same one, and what's wrong is reported
+    with where it is.
+
+    There is no puzzle for the last star, it's given for the 49 others.
+    Part 2 only reports how many of the pairs fit, and delivers the
+    Chronicle when at least one does.
 */
 
+use std::fmt::Display;
+
+use itertools::Itertools;
 use nom::{
-    branch::alt,
-    bytes::complete::tag,
-    character::complete::{line_ending, one_of},
-    combinator::map,
-    multi::{count, separated_list0},
-    sequence::{delimited, terminated, tuple},
-    IResult,
+    bytes::complete::take_while1, character::complete::line_ending, multi::separated_list1,
+    sequence::tuple, IResult,
+};
+
+use crate::{
+    answer::Answer,
+    parsing::{parse_all, ParseError},
+    solution::{Example, Solution, SolveError},
 };
 
-type Key = [u8; 5];
-type Lock = [u8; 5];
+/// The heights of the columns of a key, from its bottom row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Key(pub Vec<u8>);
+
+/// The heights of the pins of a lock, from its top row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lock(pub Vec<u8>);
+
+impl Display for Key {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.0.iter().join(","))
+    }
+}
+
+impl Display for Lock {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.0.iter().join(","))
+    }
+}
 
 #[derive(Debug)]
-struct Problem {
+pub struct Problem {
     keys: Vec<Key>,
     locks: Vec<Lock>,
+    /// The number of rows between the top and the bottom rows.
+    space: u8,
 }
 
-fn parse_key(input: &str) -> IResult<&str, Key> {
-    map(
-        delimited(
-            tuple((tag("....."), line_ending)),
-            count(terminated(count(one_of(".#"), 5), line_ending), 5),
-            tag("#####"),
-        ),
-        |key_pattern| {
-            let mut key = [0; 5];
-
-            for line in key_pattern {
-                for (i, c) in line.iter().enumerate() {
-                    if *c == '#' {
-                        key[i] += 1;
-                    }
-                }
-            }
+impl Problem {
+    pub fn keys(&self) -> &[Key] {
+        &self.keys
+    }
 
-            key
-        },
-    )(input)
-}
+    pub fn locks(&self) -> &[Lock] {
+        &self.locks
+    }
+
+    /// The number of rows between the top and the bottom rows, 5 in the
+    /// puzzle.
+    pub fn space(&self) -> u8 {
+        self.space
+    }
 
-fn parse_lock(input: &str) -> IResult<&str, Lock> {
-    map(
-        delimited(
-            tuple((tag("#####"), line_ending)),
-            count(terminated(count(one_of(".#"), 5), line_ending), 5),
-            tag("....."),
-        ),
-        |lock_pattern| {
-            let mut lock = [0; 5];
-
-            for line in lock_pattern {
-                for (i, c) in line.iter().enumerate() {
-                    if *c == '#' {
-                        lock[i] += 1;
+    /// Whether the key and the lock don't overlap in any column.
+    pub fn fits(&self, key: &Key, lock: &Lock) -> bool {
+        key.0
+            .iter()
+            .zip(&lock.0)
+            .all(|(key, lock)| u16::from(*key) + u16::from(*lock) <= u16::from(self.space))
+    }
+
+    /// The indexes of the keys and the locks that fit together, by key.
+    pub fn fitting_pairs(&self) -> Vec<(usize, usize)> {
+        // Very simple algorithm, but the number of keys and locks is small.
+        self.keys
+            .iter()
+            .enumerate()
+            .flat_map(|(key_index, key)| {
+                self.locks
+                    .iter()
+                    .enumerate()
+                    .filter(move |(_, lock)| self.fits(key, lock))
+                    .map(move |(lock_index, _)| (key_index, lock_index))
+            })
+            .collect()
+    }
+
+    /// The locks each key fits, a line per key.
+    pub fn fitting_report(&self) -> String {
+        let pairs = self.fitting_pairs();
+        self.keys
+            .iter()
+            .enumerate()
+            .map(|(key_index, key)| {
+                let locks = pairs
+                    .iter()
+                    .filter(|(key, _)| *key == key_index)
+                    .map(|(_, lock)| format!("lock {} ({})", lock, self.locks[*lock]))
+                    .collect::<Vec<_>>();
+                format!(
+                    "key {} ({}) fits {}\n",
+                    key_index,
+                    key,
+                    if locks.is_empty() {
+                        "no lock".to_string()
+                    } else {
+                        locks.join(", ")
                     }
-                }
-            }
+                )
+            })
+            .collect()
+    }
+}
 
-            lock
-        },
+fn parse_row(input: &str) -> IResult<&str, &str> {
+    take_while1(|c| c == '.' || c == '#')(input)
+}
+
+/// The schematics as their rows, they are checked afterwards.
+fn parse_input_data(input: &str) -> IResult<&str, Vec<Vec<&str>>> {
+    separated_list1(
+        tuple((line_ending, line_ending)),
+        separated_list1(line_ending, parse_row),
     )(input)
 }
 
-fn parse_input_data(input: &str) -> IResult<&str, Problem> {
-    enum Item {
-        Key(Key),
-        Lock(Lock),
-    }
-    map(
-        separated_list0(
-            tuple((line_ending, line_ending)),
-            alt((map(parse_key, Item::Key), map(parse_lock, Item::Lock))),
-        ),
-        |data| {
-            let mut keys = Vec::new();
-            let mut locks = Vec::new();
-
-            for item in data {
-                match item {
-                    Item::Key(key) => keys.push(key),
-                    Item::Lock(lock) => locks.push(lock),
-                }
-            }
+enum Schematic {
+    Key(Key),
+    Lock(Lock),
+}
 
-            Problem { keys, locks }
-        },
-    )(input)
+/// Reads a schematic, a lock when the top row is full and a key when
+/// the bottom row is. Its columns must be filled from that row, without
+/// holes.
+fn read_schematic<'a>(rows: &[&'a str]) -> Result<Schematic, (&'a str, String)> {
+    if rows.len() < 2 {
+        return Err((
+            rows[0],
+            "a schematic has at least a top and a bottom row".to_string(),
+        ));
+    }
+    let (first, last) = (rows[0], rows[rows.len() - 1]);
+    if let Some(row) = rows.iter().find(|row| row.len() != first.len()) {
+        return Err((
+            row,
+            format!(
+                "the row is {} wide, the first row of the schematic is {} wide",
+                row.len(),
+                first.len()
+            ),
+        ));
+    }
+
+    let full = |row: &str| row.bytes().all(|c| c == b'#');
+    let empty = |row: &str| row.bytes().all(|c| c == b'.');
+    let is_lock = full(first) && empty(last);
+    let is_key = empty(first) && full(last);
+    if !is_lock && !is_key {
+        return Err((
+            first,
+            "neither a lock, with a full top row and an empty bottom row, nor a key, \
+             the other way around"
+                .to_string(),
+        ));
+    }
+
+    // From the full row to the empty one
+    let ordered = if is_lock {
+        rows.to_vec()
+    } else {
+        rows.iter().rev().copied().collect()
+    };
+    let mut heights = vec![0; first.len()];
+    for (column, height) in heights.iter_mut().enumerate() {
+        let filled = ordered[1..]
+            .iter()
+            .take_while(|row| row.as_bytes()[column] == b'#')
+            .count();
+        if let Some(row) = ordered[filled + 1..]
+            .iter()
+            .find(|row| row.as_bytes()[column] == b'#')
+        {
+            return Err((
+                &row[column..],
+                format!("the column {} has a hole in it", column + 1),
+            ));
+        }
+        *height = filled as u8;
+    }
+
+    Ok(if is_lock {
+        Schematic::Lock(Lock(heights))
+    } else {
+        Schematic::Key(Key(heights))
+    })
 }
 
-pub fn day_25_part_1(data: &str) -> usize {
-    let (_, problem) = parse_input_data(data).expect("Failed to parse input data");
-
-    // Very simple algorithm, but the number of keys and locks is small.
-    problem
-        .keys
-        .iter()
-        .map(|key| {
-            problem
-                .locks
-                .iter()
-                .filter(|lock| {
-                    lock[0] + key[0] <= 5
-                        && lock[1] + key[1] <= 5
-                        && lock[2] + key[2] <= 5
-                        && lock[3] + key[3] <= 5
-                        && lock[4] + key[4] <= 5
-                })
-                .count()
-        })
-        .sum()
+pub fn day_25_parse(data: &str) -> Result<Problem, ParseError> {
+    let schematics = parse_all(25, data, parse_input_data)?;
+    let malformed = |(part, reason): (&str, String)| ParseError::malformed(25, data, part, reason);
+
+    let (width, height) = (schematics[0][0].len(), schematics[0].len());
+    if height > u8::MAX as usize {
+        return Err(malformed((
+            schematics[0][0],
+            "the schematic is too tall".to_string(),
+        )));
+    }
+    let mut keys = Vec::new();
+    let mut locks = Vec::new();
+    for rows in &schematics {
+        if rows.len() != height || rows[0].len() != width {
+            return Err(malformed((
+                rows[0],
+                format!(
+                    "the schematic is {}x{}, the first one is {}x{}",
+                    rows[0].len(),
+                    rows.len(),
+                    width,
+                    height
+                ),
+            )));
+        }
+        match read_schematic(rows).map_err(malformed)? {
+            Schematic::Key(key) => keys.push(key),
+            Schematic::Lock(lock) => locks.push(lock),
+        }
+    }
+
+    Ok(Problem {
+        keys,
+        locks,
+        space: height as u8 - 2,
+    })
 }
 
-pub fn day_25_part_2(data: &str) -> i64 {
-    42
+pub fn day_25_part_1(problem: &Problem) -> usize {
+    problem.fitting_pairs().len()
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// There is no puzzle for the last star. The Chronicle can be delivered
+/// once a key opens a lock, so the fitting pairs are reported instead.
+pub fn day_25_part_2(problem: &Problem) -> Result<String, SolveError> {
+    let fitting = problem.fitting_pairs().len();
+    if fitting == 0 {
+        return Err(SolveError::NoSolution);
+    }
+    Ok(format!(
+        "{} of {} pairs fit, the Chronicle is delivered",
+        fitting,
+        problem.keys().len() * problem.locks().len()
+    ))
+}
 
-    const EXAMPLE: &str = "#####
+const EXAMPLE: &str = "#####
 .####
 .####
 .####
@@ -164,13 +305,144 @@ mod tests {
 #.#.#
 #####";
 
+pub struct Day25;
+
+impl Solution for Day25 {
+    type Input = Problem;
+
+    fn day(&self) -> u8 {
+        25
+    }
+
+    fn name(&self) -> &'static str {
+        "Code Chronicle"
+    }
+
+    fn parse(&self, data: &str) -> Result<Self::Input, ParseError> {
+        day_25_parse(data)
+    }
+
+    fn part_1(&self, input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(day_25_part_1(input).into())
+    }
+
+    fn part_2(&self, input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(day_25_part_2(input)?.into())
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            data: EXAMPLE,
+            part_1: Some("3"),
+            part_2: Some("3 of 6 pairs fit, the Chronicle is delivered"),
+            params: &[],
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::ParseErrorKind;
+
     #[test]
     fn test_day_25_part_1() {
-        assert_eq!(day_25_part_1(EXAMPLE), 3);
+        assert_eq!(day_25_part_1(&day_25_parse(EXAMPLE).unwrap()), 3);
     }
 
     #[test]
     fn test_day_25_part_2() {
-        assert_eq!(day_25_part_2(EXAMPLE), 42);
+        assert_eq!(
+            day_25_part_2(&day_25_parse(EXAMPLE).unwrap()),
+            Ok("3 of 6 pairs fit, the Chronicle is delivered".to_string())
+        );
+
+        // The first key and the first lock overlap
+        let problem = day_25_parse(&EXAMPLE.split("\n\n").take(3).join("\n\n")).unwrap();
+        assert_eq!(day_25_part_2(&problem), Err(SolveError::NoSolution));
+    }
+
+    #[test]
+    fn test_day_25_fitting() {
+        let problem = day_25_parse(EXAMPLE).unwrap();
+        assert_eq!(problem.space(), 5);
+        assert_eq!(problem.locks()[0], Lock(vec![0, 5, 3, 4, 3]));
+        assert_eq!(problem.keys()[0], Key(vec![5, 0, 2, 1, 3]));
+        assert_eq!(problem.fitting_pairs(), vec![(1, 1), (2, 0), (2, 1)]);
+        assert_eq!(
+            problem.fitting_report(),
+            "key 0 (5,0,2,1,3) fits no lock
+key 1 (4,3,4,0,2) fits lock 1 (1,2,0,5,3)
+key 2 (3,0,2,0,1) fits lock 0 (0,5,3,4,3), lock 1 (1,2,0,5,3)
+"
+        );
+
+        // 3 columns and 4 rows, so 2 rows of space
+        let problem = day_25_parse("###\n#.#\n...\n...\n\n...\n...\n.##\n###").unwrap();
+        assert_eq!(problem.space(), 2);
+        assert_eq!(problem.locks(), [Lock(vec![1, 0, 1])]);
+        assert_eq!(problem.keys(), [Key(vec![0, 1, 1])]);
+        assert_eq!(day_25_part_1(&problem), 1);
+
+        // 200 rows, the heights add up past a u8
+        let schematic = |filled: usize, empty: usize| {
+            std::iter::repeat_n("#", filled)
+                .chain(std::iter::repeat_n(".", empty))
+                .join("\n")
+        };
+        let lock = schematic(151, 49);
+        let key = schematic(49, 151).lines().rev().join("\n");
+        let problem = day_25_parse(&format!("{}\n\n{}", lock, key)).unwrap();
+        assert_eq!(problem.space(), 198);
+        assert_eq!(problem.locks(), [Lock(vec![150])]);
+        assert_eq!(problem.keys(), [Key(vec![48])]);
+        assert_eq!(day_25_part_1(&problem), 1);
+        let key = schematic(151, 49).lines().rev().join("\n");
+        let problem = day_25_parse(&format!("{}\n\n{}", lock, key)).unwrap();
+        assert_eq!(problem.keys(), [Key(vec![150])]);
+        assert_eq!(day_25_part_1(&problem), 0);
+    }
+
+    #[test]
+    fn test_day_25_malformed() {
+        let malformed = |data: &str| {
+            let error = day_25_parse(data).unwrap_err();
+            match error.kind {
+                ParseErrorKind::Malformed(reason) => (error.line, error.column, reason),
+                kind => panic!("{:?} for {:?}", kind, data),
+            }
+        };
+        assert_eq!(
+            malformed("###\n#.#\n..\n..."),
+            (
+                3,
+                1,
+                "the row is 2 wide, the first row of the schematic is 3 wide".to_string()
+            )
+        );
+        assert_eq!(
+            malformed("###\n..#\n#..\n..."),
+            (3, 1, "the column 1 has a hole in it".to_string())
+        );
+        assert_eq!(
+            malformed("...\n#.#\n###\n..."),
+            (1, 1, "neither a lock, with a full top row and an empty bottom row, nor a key, the other way around".to_string())
+        );
+        assert_eq!(
+            malformed("###\n...\n...\n\n...\n###"),
+            (
+                5,
+                1,
+                "the schematic is 3x2, the first one is 3x3".to_string()
+            )
+        );
+        let error = day_25_parse("###\n#..\n.#.\n...").unwrap_err();
+        assert_eq!(
+            error.report(),
+            "day 25: malformed input, the column 2 has a hole in it at line 3, column 2
+  |
+3 | .#.
+  |  ^"
+        );
     }
 }
@@ -0,0 +1,251 @@
+/*
+    The directions on a grid, I had a `Direction` enum in almost every
+    grid day, each with its own rotation and its own way to move
+    without falling off the map. `Direction` is the 4 orthogonal ones,
+    `Direction8` adds the diagonals.
+
+    Rows grow downwards, so going up is a negative row offset.
+*/
+
+use std::fmt::Display;
+
+use nom::{character::complete::one_of, combinator::map_opt, IResult};
+
+use crate::grid::{offset_position, GridCell, Position};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    /// The 4 directions, clockwise from up.
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    /// The position of the direction in `ALL`, to index arrays by direction.
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    pub fn rotate_cw(self) -> Direction {
+        Direction::ALL[(self.index() + 1) % 4]
+    }
+
+    pub fn rotate_ccw(self) -> Direction {
+        Direction::ALL[(self.index() + 3) % 4]
+    }
+
+    pub fn opposite(self) -> Direction {
+        Direction::ALL[(self.index() + 2) % 4]
+    }
+
+    /// The number of quarter turns to face the other direction, a U-turn is 2.
+    pub fn turns_to(self, other: Direction) -> usize {
+        match (other.index() + 4 - self.index()) % 4 {
+            3 => 1,
+            turns => turns,
+        }
+    }
+
+    /// The (row, col) offset of a step in this direction.
+    pub fn offset(self) -> (isize, isize) {
+        match self {
+            Direction::Up => (-1, 0),
+            Direction::Right => (0, 1),
+            Direction::Down => (1, 0),
+            Direction::Left => (0, -1),
+        }
+    }
+
+    /// The next position in this direction, if it's still in the grid.
+    pub fn step(self, dim: (usize, usize), position: Position) -> Option<Position> {
+        offset_position(dim, position, self.offset())
+    }
+
+    pub fn is_vertical(self) -> bool {
+        matches!(self, Direction::Up | Direction::Down)
+    }
+
+    /// From an arrow: `^`, `v`, `<` or `>`.
+    pub fn from_arrow(c: char) -> Option<Direction> {
+        match c {
+            '^' => Some(Direction::Up),
+            '>' => Some(Direction::Right),
+            'v' => Some(Direction::Down),
+            '<' => Some(Direction::Left),
+            _ => None,
+        }
+    }
+
+    pub fn to_arrow(self) -> char {
+        match self {
+            Direction::Up => '^',
+            Direction::Right => '>',
+            Direction::Down => 'v',
+            Direction::Left => '<',
+        }
+    }
+
+    /// From a letter: `U`, `R`, `D` or `L`.
+    pub fn from_letter(c: char) -> Option<Direction> {
+        match c {
+            'U' => Some(Direction::Up),
+            'R' => Some(Direction::Right),
+            'D' => Some(Direction::Down),
+            'L' => Some(Direction::Left),
+            _ => None,
+        }
+    }
+
+    pub fn to_letter(self) -> char {
+        match self {
+            Direction::Up => 'U',
+            Direction::Right => 'R',
+            Direction::Down => 'D',
+            Direction::Left => 'L',
+        }
+    }
+}
+
+impl Display for Direction {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.to_arrow())
+    }
+}
+
+/// Directions in a grid are usually written as arrows.
+impl GridCell for Direction {
+    fn from_char(c: char) -> Option<Self> {
+        Direction::from_arrow(c)
+    }
+
+    fn to_char(&self) -> char {
+        self.to_arrow()
+    }
+}
+
+pub fn parse_arrow(input: &str) -> IResult<&str, Direction> {
+    map_opt(one_of("^>v<"), Direction::from_arrow)(input)
+}
+
+pub fn parse_letter(input: &str) -> IResult<&str, Direction> {
+    map_opt(one_of("URDL"), Direction::from_letter)(input)
+}
+
+/// The 8 directions, diagonals included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction8 {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Direction8 {
+    /// The 8 directions, clockwise from up.
+    pub const ALL: [Direction8; 8] = [
+        Direction8::Up,
+        Direction8::UpRight,
+        Direction8::Right,
+        Direction8::DownRight,
+        Direction8::Down,
+        Direction8::DownLeft,
+        Direction8::Left,
+        Direction8::UpLeft,
+    ];
+
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Rotates by 45 degrees clockwise.
+    pub fn rotate_cw(self) -> Direction8 {
+        Direction8::ALL[(self.index() + 1) % 8]
+    }
+
+    /// Rotates by 45 degrees counter-clockwise.
+    pub fn rotate_ccw(self) -> Direction8 {
+        Direction8::ALL[(self.index() + 7) % 8]
+    }
+
+    pub fn opposite(self) -> Direction8 {
+        Direction8::ALL[(self.index() + 4) % 8]
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        self.index() % 2 == 1
+    }
+
+    pub fn offset(self) -> (isize, isize) {
+        match self {
+            Direction8::Up => (-1, 0),
+            Direction8::UpRight => (-1, 1),
+            Direction8::Right => (0, 1),
+            Direction8::DownRight => (1, 1),
+            Direction8::Down => (1, 0),
+            Direction8::DownLeft => (1, -1),
+            Direction8::Left => (0, -1),
+            Direction8::UpLeft => (-1, -1),
+        }
+    }
+
+    pub fn step(self, dim: (usize, usize), position: Position) -> Option<Position> {
+        offset_position(dim, position, self.offset())
+    }
+}
+
+impl From<Direction> for Direction8 {
+    fn from(direction: Direction) -> Self {
+        Direction8::ALL[direction.index() * 2]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_direction() {
+        assert_eq!(Direction::Up.rotate_cw(), Direction::Right);
+        assert_eq!(Direction::Up.rotate_ccw(), Direction::Left);
+        assert_eq!(Direction::Left.opposite(), Direction::Right);
+        assert_eq!(Direction::Up.turns_to(Direction::Up), 0);
+        assert_eq!(Direction::Up.turns_to(Direction::Left), 1);
+        assert_eq!(Direction::Left.turns_to(Direction::Up), 1);
+        assert_eq!(Direction::Down.turns_to(Direction::Up), 2);
+        assert_eq!(Direction::Up.step((3, 3), (0, 1)), None);
+        assert_eq!(Direction::Right.step((3, 3), (0, 1)), Some((0, 2)));
+        assert_eq!(Direction::Right.step((3, 3), (0, 2)), None);
+        assert_eq!(
+            "^>v<"
+                .chars()
+                .filter_map(Direction::from_arrow)
+                .collect::<Vec<_>>(),
+            Direction::ALL
+        );
+        assert_eq!(parse_letter("LU"), Ok(("U", Direction::Left)));
+        assert!(parse_arrow("A").is_err());
+    }
+
+    #[test]
+    fn test_direction_8() {
+        assert_eq!(Direction8::Up.rotate_ccw(), Direction8::UpLeft);
+        assert_eq!(Direction8::DownRight.opposite(), Direction8::UpLeft);
+        assert_eq!(Direction8::from(Direction::Left), Direction8::Left);
+        assert!(Direction8::UpRight.is_diagonal());
+        assert_eq!(Direction8::DownLeft.step((3, 3), (0, 1)), Some((1, 0)));
+        assert_eq!(Direction8::UpLeft.step((3, 3), (0, 1)), None);
+    }
+}
@@ -0,0 +1,290 @@
+/*
+    Many days are a grid of characters, so here are the bits I kept
+    rewriting: parsing the grid into an `Array2`, walking to the
+    neighbors without falling off the edges, finding the start and the
+    exit, and printing the grid back when something goes wrong.
+
+    The parser checks that the grid is a rectangle, a row longer or
+    shorter than the first one stops the grid there, and the parsing of
+    the day fails on what's left.
+*/
+
+use std::{fmt::Display, str::FromStr};
+
+use ndarray::Array2;
+use nom::{
+    character::complete::{line_ending, none_of, one_of},
+    combinator::map_opt,
+    multi::{count, many1},
+    sequence::preceded,
+    IResult, Parser,
+};
+
+use crate::solution::SolveError;
+
+/// A position in a grid, as (row, col).
+pub type Position = (usize, usize);
+
+/// The offsets to the 4 orthogonal neighbors: up, down, left and right.
+pub const OFFSETS_4: [(isize, isize); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+
+/// The offsets to the 8 neighbors, diagonals included.
+pub const OFFSETS_8: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+/// The size of a grid, written `WIDTHxHEIGHT` like `101x103`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Size {
+    pub width: usize,
+    pub height: usize,
+}
+
+impl FromStr for Size {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || format!("`{}` is not WIDTHxHEIGHT", s);
+        let (width, height) = s.split_once('x').ok_or_else(error)?;
+        let width = width.parse::<usize>().map_err(|_| error())?;
+        let height = height.parse::<usize>().map_err(|_| error())?;
+        if width == 0 || height == 0 {
+            return Err(format!("`{}` is an empty grid", s));
+        }
+        Ok(Size { width, height })
+    }
+}
+
+impl Display for Size {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}x{}", self.width, self.height)
+    }
+}
+
+/// A cell that is written as a single character in the puzzle input.
+pub trait GridCell: Sized {
+    fn from_char(c: char) -> Option<Self>;
+
+    fn to_char(&self) -> char;
+}
+
+impl GridCell for char {
+    fn from_char(c: char) -> Option<Self> {
+        Some(c)
+    }
+
+    fn to_char(&self) -> char {
+        *self
+    }
+}
+
+/// Parses a rectangular grid, one line per row, each cell being parsed
+/// by `cell`.
+pub fn parse_grid<'a, T, F>(mut cell: F) -> impl FnMut(&'a str) -> IResult<&'a str, Array2<T>>
+where
+    F: Parser<&'a str, T, nom::error::Error<&'a str>>,
+{
+    move |input: &'a str| {
+        let (mut input, mut cells) = many1(|i| cell.parse(i))(input)?;
+        let ncols = cells.len();
+        loop {
+            match preceded(line_ending, count(|i| cell.parse(i), ncols))(input) {
+                Ok((rest, row)) => {
+                    cells.extend(row);
+                    input = rest;
+                }
+                Err(nom::Err::Error(_)) => break,
+                Err(error) => return Err(error),
+            }
+        }
+        let nrows = cells.len() / ncols;
+        let grid = Array2::from_shape_vec((nrows, ncols), cells)
+            .expect("The rows of the grid all have the same length");
+        Ok((input, grid))
+    }
+}
+
+/// Parses a grid of characters, only the `allowed` ones.
+pub fn parse_char_grid<'a>(
+    allowed: &'static str,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Array2<char>> {
+    parse_grid(one_of(allowed))
+}
+
+/// Parses a grid of typed cells.
+pub fn parse_cells<T: GridCell>(input: &str) -> IResult<&str, Array2<T>> {
+    parse_grid(map_opt(none_of("\r\n"), T::from_char))(input)
+}
+
+/// The position at the given offset, if it's still in the grid.
+pub fn offset_position(
+    dim: (usize, usize),
+    (row, col): Position,
+    (drow, dcol): (isize, isize),
+) -> Option<Position> {
+    let row = row.checked_add_signed(drow)?;
+    let col = col.checked_add_signed(dcol)?;
+    (row < dim.0 && col < dim.1).then_some((row, col))
+}
+
+/// The number of orthogonal steps between two positions.
+pub fn manhattan_distance(a: Position, b: Position) -> usize {
+    a.0.abs_diff(b.0) + a.1.abs_diff(b.1)
+}
+
+/// The orthogonal neighbors of a position that are in the grid.
+pub fn neighbors_4(dim: (usize, usize), position: Position) -> impl Iterator<Item = Position> {
+    OFFSETS_4
+        .into_iter()
+        .filter_map(move |offset| offset_position(dim, position, offset))
+}
+
+/// The neighbors of a position that are in the grid, diagonals included.
+pub fn neighbors_8(dim: (usize, usize), position: Position) -> impl Iterator<Item = Position> {
+    OFFSETS_8
+        .into_iter()
+        .filter_map(move |offset| offset_position(dim, position, offset))
+}
+
+/// The position of the first cell equal to `cell`, row by row.
+pub fn find_cell<T: PartialEq>(grid: &Array2<T>, cell: &T) -> Option<Position> {
+    grid.indexed_iter()
+        .find(|(_, candidate)| *candidate == cell)
+        .map(|(position, _)| position)
+}
+
+/// The positions of the start and of the exit cells, both must be in the grid.
+pub fn find_start_and_exit<T: PartialEq>(
+    grid: &Array2<T>,
+    start: &T,
+    exit: &T,
+) -> Result<(Position, Position), SolveError> {
+    Ok((
+        find_cell(grid, start).ok_or(SolveError::MissingCell("start"))?,
+        find_cell(grid, exit).ok_or(SolveError::MissingCell("exit"))?,
+    ))
+}
+
+/// Renders the grid with a character per cell, one line per row.
+pub fn render_with<T>(grid: &Array2<T>, mut cell_char: impl FnMut(Position, &T) -> char) -> String {
+    let mut output = String::with_capacity(grid.nrows() * (grid.ncols() + 1));
+    for (row, line) in grid.outer_iter().enumerate() {
+        for (col, cell) in line.iter().enumerate() {
+            output.push(cell_char((row, col), cell));
+        }
+        output.push('\n');
+    }
+    output
+}
+
+/// Renders the grid as it was written in the input.
+pub fn render<T: GridCell>(grid: &Array2<T>) -> String {
+    render_with(grid, |_, cell| cell.to_char())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::{parse_all, ParseErrorKind};
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Cell {
+        Empty,
+        Wall,
+        Start,
+        Exit,
+    }
+
+    impl GridCell for Cell {
+        fn from_char(c: char) -> Option<Self> {
+            match c {
+                '.' => Some(Cell::Empty),
+                '#' => Some(Cell::Wall),
+                'S' => Some(Cell::Start),
+                'E' => Some(Cell::Exit),
+                _ => None,
+            }
+        }
+
+        fn to_char(&self) -> char {
+            match self {
+                Cell::Empty => '.',
+                Cell::Wall => '#',
+                Cell::Start => 'S',
+                Cell::Exit => 'E',
+            }
+        }
+    }
+
+    const EXAMPLE: &str = "#####
+#S..#
+#.#E#
+#####
+";
+
+    #[test]
+    fn test_parse_and_render() {
+        let grid = parse_all(0, EXAMPLE, parse_cells::<Cell>).unwrap();
+        assert_eq!(grid.dim(), (4, 5));
+        assert_eq!(grid[(2, 2)], Cell::Wall);
+        assert_eq!(render(&grid), EXAMPLE);
+        assert_eq!(
+            find_start_and_exit(&grid, &Cell::Start, &Cell::Exit),
+            Ok(((1, 1), (2, 3)))
+        );
+        let no_exit = grid.mapv(|cell| {
+            if cell == Cell::Exit {
+                Cell::Empty
+            } else {
+                cell
+            }
+        });
+        assert_eq!(
+            find_start_and_exit(&no_exit, &Cell::Start, &Cell::Exit),
+            Err(SolveError::MissingCell("exit"))
+        );
+
+        let error = parse_all(0, "#..\n#...\n#..", parse_char_grid("#.")).unwrap_err();
+        assert_eq!(error.kind, ParseErrorKind::Unconsumed);
+        assert_eq!((error.line, error.column), (2, 4));
+        assert!(parse_all(0, "#.x", parse_cells::<Cell>).is_err());
+    }
+
+    #[test]
+    fn test_size() {
+        assert_eq!(
+            "101x103".parse(),
+            Ok(Size {
+                width: 101,
+                height: 103
+            })
+        );
+        assert_eq!(Size::from_str("7x7").unwrap().to_string(), "7x7");
+        assert!("0x7".parse::<Size>().is_err());
+        assert!("7".parse::<Size>().is_err());
+        assert!("7xa".parse::<Size>().is_err());
+    }
+
+    #[test]
+    fn test_neighbors() {
+        assert_eq!(
+            neighbors_4((3, 4), (0, 0)).collect::<Vec<_>>(),
+            vec![(1, 0), (0, 1)]
+        );
+        assert_eq!(neighbors_4((3, 4), (1, 1)).count(), 4);
+        assert_eq!(
+            neighbors_8((3, 4), (2, 3)).collect::<Vec<_>>(),
+            vec![(1, 2), (1, 3), (2, 2)]
+        );
+        assert_eq!(neighbors_8((3, 4), (1, 1)).count(), 8);
+        assert_eq!(offset_position((3, 4), (2, 3), (0, 1)), None);
+        assert_eq!(manhattan_distance((2, 3), (4, 1)), 4);
+    }
+}
@@ -0,0 +1,129 @@
+/*
+    Puzzle inputs are loaded at runtime, so the crate builds without
+    any input file and we can run a solver on someone else's input.
+*/
+
+use std::{
+    fmt::Display,
+    io::Read,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum InputSource {
+    File(PathBuf),
+    Stdin,
+}
+
+impl InputSource {
+    /// The default location of a day input, `<dir>/day_XX.txt`.
+    pub fn for_day(inputs_dir: &Path, day: u8) -> Self {
+        InputSource::File(inputs_dir.join(format!("day_{:02}.txt", day)))
+    }
+
+    pub fn load(&self) -> Result<String, InputError> {
+        match self {
+            InputSource::File(path) => std::fs::read_to_string(path).map_err(|error| {
+                if error.kind() == std::io::ErrorKind::NotFound {
+                    InputError::Missing(path.clone())
+                } else {
+                    InputError::Io(self.clone(), error)
+                }
+            }),
+            InputSource::Stdin => {
+                let mut data = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut data)
+                    .map_err(|error| InputError::Io(self.clone(), error))?;
+                Ok(data)
+            }
+        }
+    }
+}
+
+impl Display for InputSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            InputSource::File(path) => write!(f, "{}", path.display()),
+            InputSource::Stdin => write!(f, "<stdin>"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum InputError {
+    Missing(PathBuf),
+    Io(InputSource, std::io::Error),
+}
+
+impl Display for InputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            InputError::Missing(path) => write!(f, "missing input, {} not found", path.display()),
+            InputError::Io(source, error) => write!(f, "failed to read {}: {}", source, error),
+        }
+    }
+}
+
+/// An `--input` argument: `[DAY=]PATH`, where `-` is the standard input.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InputOverride {
+    pub day: Option<u8>,
+    pub source: InputSource,
+}
+
+impl FromStr for InputOverride {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // A path may contain a '=', so only consider it a day
+        // when what comes before is a number.
+        let (day, path) = match s.split_once('=') {
+            Some((day, path)) if day.parse::<u8>().is_ok() => {
+                (Some(day.parse::<u8>().unwrap()), path)
+            }
+            _ => (None, s),
+        };
+        if path.is_empty() {
+            return Err(format!("`{}` has an empty path", s));
+        }
+        let source = if path == "-" {
+            InputSource::Stdin
+        } else {
+            InputSource::File(PathBuf::from(path))
+        };
+        Ok(InputOverride { day, source })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_input_override() {
+        assert_eq!(
+            "-".parse(),
+            Ok(InputOverride {
+                day: None,
+                source: InputSource::Stdin
+            })
+        );
+        assert_eq!(
+            "5=other/day_05.txt".parse(),
+            Ok(InputOverride {
+                day: Some(5),
+                source: InputSource::File(PathBuf::from("other/day_05.txt"))
+            })
+        );
+        assert_eq!(
+            "a=b.txt".parse(),
+            Ok(InputOverride {
+                day: None,
+                source: InputSource::File(PathBuf::from("a=b.txt"))
+            })
+        );
+        assert!("5=".parse::<InputOverride>().is_err());
+    }
+}
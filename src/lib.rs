@@ -0,0 +1,48 @@
+/*
+    The solutions of the Advent of Code 2024, as a library so the days and
+    their building blocks (the clique search of day 23, the chronospatial
+    computer of day 17, the pathfinding of days 16 and 18…) can be used elsewhere.
+    The commands are in `commands`, the binary in `main.rs` only declares
+    their arguments on top of it.
+*/
+
+pub mod answer;
+pub mod answers;
+pub mod bench;
+pub mod chronospatial;
+pub mod commands;
+pub mod direction;
+pub mod grid;
+pub mod inputs;
+pub mod output;
+pub mod params;
+pub mod parsing;
+pub mod pathfinding;
+pub mod runner;
+pub mod solution;
+
+pub mod day_01;
+pub mod day_02;
+pub mod day_03;
+pub mod day_04;
+pub mod day_05;
+pub mod day_06;
+pub mod day_07;
+pub mod day_08;
+pub mod day_09;
+pub mod day_10;
+pub mod day_11;
+pub mod day_12;
+pub mod day_13;
+pub mod day_14;
+pub mod day_15;
+pub mod day_16;
+pub mod day_17;
+pub mod day_18;
+pub mod day_19;
+pub mod day_20;
+pub mod day_21;
+pub mod day_22;
+pub mod day_23;
+pub mod day_24;
+pub mod day_25;
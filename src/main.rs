@@ -1,74 +1,295 @@
-use paste::paste;
-use std::fmt::Display;
-
-mod day_01;
-mod day_02;
-mod day_03;
-mod day_04;
-mod day_05;
-mod day_06;
-mod day_07;
-mod day_08;
-mod day_09;
-mod day_10;
-mod day_11;
-mod day_12;
-mod day_13;
-mod day_14;
-mod day_15;
-mod day_16;
-mod day_17;
-mod day_18;
-mod day_19;
-mod day_20;
-mod day_21;
-mod day_22;
-mod day_23;
-mod day_24;
-mod day_25;
-
-fn execute_day<F, G, D1, D2>(day: &str, data: &str, part_1: F, part_2: G)
-where
-    F: Fn(&str) -> D1,
-    G: Fn(&str) -> D2,
-    D1: Display,
-    D2: Display,
-{
-    let now = std::time::Instant::now();
-    println!("Day {}, part 1: {}", day, part_1(data));
-    println!("Day {}, part 2: {}", day, part_2(data));
-    println!("Time day {}: {:?}", day, now.elapsed());
+use advent_of_code_2024_rust::{
+    commands::{
+        self, AdderOptions, BenchOptions, CircuitOptions, CommandError, DaySelection,
+        RobotsOptions, RunOptions,
+    },
+    day_14::FrameFormat,
+    grid::Size,
+    inputs::InputOverride,
+    output::Format,
+    params::ParamOverride,
+    solution::SOLUTIONS,
+};
+use clap::{Args, CommandFactory, Parser, Subcommand};
+use std::path::PathBuf;
+
+/// Runs the Advent of Code 2024 solutions.
+#[derive(Parser, Debug)]
+#[command(about, args_conflicts_with_subcommands = true)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[command(flatten)]
+    selection: Selection,
+
+    /// How the results are printed.
+    #[arg(short, long, value_enum, default_value_t = Format::Table)]
+    format: Format,
+
+    /// File of the expected answers the results are verified against.
+    #[arg(long, env = "AOC_ANSWERS_FILE", default_value = "answers.toml")]
+    answers: PathBuf,
+
+    /// Write the answers of this run to the answers file, as the new
+    /// baseline, instead of failing on the ones that differ.
+    #[arg(long, conflicts_with = "examples")]
+    record: bool,
+
+    /// Run the puzzle examples instead of the inputs, and verify the
+    /// results against their known answers.
+    #[arg(short, long)]
+    examples: bool,
 }
 
-macro_rules! execute_day {
-    ($day:expr, $part_1:expr, $part_2:expr) => {
-        execute_day(
-            $day,
-            include_str!(concat!("../inputs/day_", $day, ".txt")),
-            $part_1,
-            $part_2,
-        );
-    };
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Measures the parsing and the parts of the selected days over
+    /// several runs, and compares them to a saved baseline.
+    Bench(BenchArgs),
+    /// Lists the registered days.
+    List,
+    /// Exports the frames of the day 14 robots over a range of seconds.
+    Robots(RobotsArgs),
+    /// Exports the day 24 circuit as a Graphviz DOT graph.
+    Circuit(CircuitArgs),
+    /// Adds numbers with the day 24 circuit and checks the sums.
+    Adder(AdderArgs),
+    /// Prints which key of day 25 fits which lock.
+    Locks(DayInput),
 }
 
-macro_rules! execute_days {
-    ($($day:literal),*) => {
-        $(
-            paste! {
-                execute_day!(
-                    $day,
-                    [<day_ $day>]::[<day_ $day _part_1>],
-                    [<day_ $day>]::[<day_ $day _part_2>]
-                );
-            }
-        )*
-    };
+/// The days and parts to run, and where their inputs are.
+#[derive(Args, Debug)]
+struct Selection {
+    /// Days to run, either a single day (`5`) or an inclusive range (`5-12`).
+    /// Every registered day is run when none is given.
+    days: Vec<DaySelection>,
+
+    /// Only run the given part of each day.
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    part: Option<u8>,
+
+    /// Directory containing the `day_XX.txt` puzzle inputs.
+    #[arg(long, env = "AOC_INPUTS_DIR", default_value = "inputs")]
+    inputs_dir: PathBuf,
+
+    /// Input file to use instead of the one in the inputs directory, as
+    /// `DAY=PATH`, or just `PATH` when a single day is selected.
+    /// A `-` path reads the input from the standard input.
+    #[arg(short, long = "input", value_name = "[DAY=]PATH")]
+    inputs: Vec<InputOverride>,
+
+    /// Parameter of a day, as `DAY.NAME=VALUE`, or just `NAME=VALUE` when
+    /// a single day is selected. `list` shows the parameters of each day.
+    #[arg(long = "param", value_name = "[DAY.]NAME=VALUE")]
+    params: Vec<ParamOverride>,
+}
+
+#[derive(Args, Debug)]
+struct BenchArgs {
+    #[command(flatten)]
+    selection: Selection,
+
+    /// Number of measured runs of each day.
+    #[arg(short = 'n', long, default_value_t = 10, value_parser = clap::value_parser!(u32).range(1..))]
+    runs: u32,
+
+    /// Number of runs before the measured ones, to warm up the caches.
+    #[arg(short, long, default_value_t = 2)]
+    warmup: u32,
+
+    /// File of the baseline medians the results are compared to.
+    #[arg(long, default_value = "bench.toml")]
+    baseline: PathBuf,
+
+    /// Save the medians of this run in the baseline file.
+    #[arg(long)]
+    save_baseline: bool,
+
+    /// Slowdown of the median, in percent, above which a step is
+    /// reported as a regression.
+    #[arg(short, long, default_value_t = 10.0)]
+    threshold: f64,
+}
+
+/// The input of the commands working on a single day.
+#[derive(Args, Debug)]
+struct DayInput {
+    /// Input file to use instead of the one in the inputs directory.
+    #[arg(short, long, value_name = "PATH")]
+    input: Option<PathBuf>,
+
+    /// Directory containing the `day_XX.txt` puzzle inputs.
+    #[arg(long, env = "AOC_INPUTS_DIR", default_value = "inputs")]
+    inputs_dir: PathBuf,
+}
+
+#[derive(Args, Debug)]
+struct RobotsArgs {
+    #[command(flatten)]
+    input: DayInput,
+
+    /// Size of the room.
+    #[arg(long, default_value = "101x103", value_name = "WIDTHxHEIGHT")]
+    size: Size,
+
+    /// First second to export.
+    #[arg(long, default_value_t = 0, value_parser = clap::value_parser!(i64).range(0..))]
+    from: i64,
+
+    /// Last second to export, the first one when not given.
+    #[arg(long, value_parser = clap::value_parser!(i64).range(0..))]
+    to: Option<i64>,
+
+    /// Export the seconds around the tree found by part 2 instead, this
+    /// many before and after it.
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        conflicts_with_all = ["from", "to"],
+        value_parser = clap::value_parser!(i64).range(0..)
+    )]
+    around_tree: Option<i64>,
+
+    /// Format of the frames.
+    #[arg(short, long, value_enum, default_value_t = FrameFormat::Ascii)]
+    format: FrameFormat,
+
+    /// Directory the frames are written to, one file per frame, or `-`
+    /// to print them.
+    #[arg(short, long, default_value = "frames")]
+    output: PathBuf,
+}
+
+#[derive(Args, Debug)]
+struct CircuitArgs {
+    #[command(flatten)]
+    input: DayInput,
+
+    /// Wires to draw in red, with the wires they go to.
+    #[arg(long, value_name = "WIRE,...", value_delimiter = ',')]
+    highlight: Vec<String>,
+
+    /// Draw the wires found by part 2 in red too, and print why on the
+    /// standard error.
+    #[arg(long)]
+    swapped: bool,
+
+    /// File the graph is written to, or `-` to print it.
+    #[arg(short, long, default_value = "-")]
+    output: PathBuf,
+}
+
+#[derive(Args, Debug)]
+struct AdderArgs {
+    #[command(flatten)]
+    input: DayInput,
+
+    /// Number set on the x wires, the one of the input when not given.
+    #[arg(requires = "y")]
+    x: Option<u64>,
+
+    /// Number set on the y wires.
+    y: Option<u64>,
+
+    /// Add this many pairs of random numbers instead, only the wrong
+    /// sums are printed.
+    #[arg(short, long, value_name = "COUNT", conflicts_with = "x")]
+    random: Option<usize>,
+
+    /// Seed of the random numbers.
+    #[arg(long, default_value_t = 2024, requires = "random")]
+    seed: u64,
+}
+
+impl From<Selection> for commands::Selection {
+    fn from(selection: Selection) -> Self {
+        commands::Selection {
+            days: selection.days,
+            part: selection.part,
+            inputs_dir: selection.inputs_dir,
+            inputs: selection.inputs,
+            params: selection.params,
+        }
+    }
+}
+
+impl From<DayInput> for commands::DayInput {
+    fn from(input: DayInput) -> Self {
+        commands::DayInput {
+            input: input.input,
+            inputs_dir: input.inputs_dir,
+        }
+    }
+}
+
+/// Exits with the error of a command, the usage errors the way clap
+/// reports its own.
+fn exit_on_error(result: Result<(), CommandError>) {
+    match result {
+        Ok(()) => {}
+        Err(CommandError::Usage(message)) => Cli::command()
+            .error(clap::error::ErrorKind::InvalidValue, message)
+            .exit(),
+        Err(CommandError::Failed) => std::process::exit(1),
+        Err(error) => {
+            eprintln!("{}", error);
+            std::process::exit(1);
+        }
+    }
 }
 
 fn main() {
-    execute_days!("01");
-    execute_days!("02", "03", "04", "05", "06", "07", "08");
-    execute_days!("09", "10", "11", "12", "13", "14", "15");
-    execute_days!("16", "17", "18", "19", "20", "21", "22");
-    execute_days!("23", "24", "25");
+    let cli = Cli::parse();
+    let days = SOLUTIONS;
+
+    let result = match cli.command {
+        Some(Command::Bench(args)) => commands::bench(
+            &BenchOptions {
+                selection: args.selection.into(),
+                runs: args.runs,
+                warmup: args.warmup,
+                baseline: args.baseline,
+                save_baseline: args.save_baseline,
+                threshold: args.threshold,
+            },
+            days,
+        ),
+        Some(Command::List) => {
+            commands::list(days);
+            Ok(())
+        }
+        Some(Command::Robots(args)) => commands::robots(&RobotsOptions {
+            input: args.input.into(),
+            size: args.size,
+            times: args.from..=args.to.unwrap_or(args.from),
+            around_tree: args.around_tree,
+            format: args.format,
+            output: args.output,
+        }),
+        Some(Command::Circuit(args)) => commands::circuit(&CircuitOptions {
+            input: args.input.into(),
+            highlight: args.highlight,
+            swapped: args.swapped,
+            output: args.output,
+        }),
+        Some(Command::Adder(args)) => commands::adder(&AdderOptions {
+            input: args.input.into(),
+            numbers: args.x.zip(args.y),
+            random: args.random.map(|count| (count, args.seed)),
+        }),
+        Some(Command::Locks(input)) => commands::locks(&input.into()),
+        None => commands::run(
+            &RunOptions {
+                selection: cli.selection.into(),
+                format: cli.format,
+                answers: cli.answers,
+                record: cli.record,
+                examples: cli.examples,
+            },
+            days,
+        ),
+    };
+    exit_on_error(result);
 }
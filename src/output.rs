@@ -0,0 +1,238 @@
+/*
+    Results of the runner, printed as they come either as an aligned table
+    for humans, or as JSON/CSV records for scripts and dashboards.
+*/
+
+use std::time::Duration;
+
+use crate::{answer::Answer, answers::Verdict};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+    Table,
+    Json,
+    Csv,
+}
+
+#[derive(Debug)]
+pub struct PartResult {
+    pub day: u8,
+    pub part: u8,
+    pub outcome: Result<Answer, String>,
+    /// Time spent parsing the input, shared by both parts of a day.
+    pub parse_elapsed: Duration,
+    /// Time spent solving the part, without the parsing.
+    pub elapsed: Duration,
+    /// Comparison with the expected answer, unknown when the part failed.
+    pub verdict: Verdict,
+}
+
+impl PartResult {
+    pub fn status(&self) -> &'static str {
+        match (&self.outcome, &self.verdict) {
+            (Err(_), _) => "failed",
+            (Ok(_), Verdict::Pass) => "pass",
+            (Ok(_), Verdict::Fail(_)) => "wrong",
+            (Ok(_), Verdict::Unknown) => "unknown",
+        }
+    }
+
+    fn expected(&self) -> Option<&Answer> {
+        match &self.verdict {
+            Verdict::Fail(expected) => Some(expected),
+            _ => None,
+        }
+    }
+}
+
+/// Prints the results one record at a time, so long runs show progress.
+pub struct Printer {
+    format: Format,
+    records: usize,
+}
+
+impl Printer {
+    pub fn new(format: Format) -> Self {
+        match format {
+            Format::Table => println!(
+                "{:<4} {:<5} {:<8} {:>12} {:>12}  Answer",
+                "Day", "Part", "Status", "Parse", "Time"
+            ),
+            Format::Json => println!("["),
+            Format::Csv => {
+                println!("day,part,answer,type,status,expected,parse_ns,elapsed_ns,success,error")
+            }
+        }
+        Printer { format, records: 0 }
+    }
+
+    pub fn print(&mut self, result: &PartResult) {
+        match self.format {
+            Format::Table => println!(
+                "{:<4} {:<5} {:<8} {:>12} {:>12}  {}",
+                format!("{:02}", result.day),
+                result.part,
+                result.status(),
+                format!("{:.2?}", result.parse_elapsed),
+                format!("{:.2?}", result.elapsed),
+                match (&result.outcome, result.expected()) {
+                    (Ok(answer), Some(expected)) => format!("{} (expected {})", answer, expected),
+                    (Ok(answer), None) => answer.to_string(),
+                    (Err(error), _) => error.clone(),
+                }
+            ),
+            Format::Json => {
+                if self.records > 0 {
+                    println!(",");
+                }
+                print!("  {}", json_record(result));
+            }
+            Format::Csv => println!("{}", csv_record(result)),
+        }
+        self.records += 1;
+    }
+
+    pub fn finish(self) {
+        if self.format == Format::Json {
+            if self.records > 0 {
+                println!();
+            }
+            println!("]");
+        }
+    }
+}
+
+fn json_string(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+    escaped.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+fn json_answer(answer: &Answer) -> String {
+    match answer {
+        Answer::Text(text) => json_string(text),
+        answer => answer.to_string(),
+    }
+}
+
+fn json_record(result: &PartResult) -> String {
+    let (answer, kind, error) = match &result.outcome {
+        Ok(answer) => (json_answer(answer), json_string(answer.kind()), None),
+        Err(error) => ("null".to_string(), "null".to_string(), Some(error)),
+    };
+    format!(
+        "{{\"day\": {}, \"part\": {}, \"answer\": {}, \"type\": {}, \"status\": {}, \"expected\": {}, \"parse_ns\": {}, \"elapsed_ns\": {}, \"success\": {}, \"error\": {}}}",
+        result.day,
+        result.part,
+        answer,
+        kind,
+        json_string(result.status()),
+        result.expected().map_or("null".to_string(), json_answer),
+        result.parse_elapsed.as_nanos(),
+        result.elapsed.as_nanos(),
+        error.is_none(),
+        error.map_or("null".to_string(), |error| json_string(error)),
+    )
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn csv_record(result: &PartResult) -> String {
+    let (answer, kind, error) = match &result.outcome {
+        Ok(answer) => (answer.to_string(), answer.kind(), ""),
+        Err(error) => (String::new(), "", error.as_str()),
+    };
+    format!(
+        "{},{},{},{},{},{},{},{},{},{}",
+        result.day,
+        result.part,
+        csv_field(&answer),
+        kind,
+        result.status(),
+        csv_field(
+            &result
+                .expected()
+                .map_or(String::new(), |expected| expected.to_string())
+        ),
+        result.parse_elapsed.as_nanos(),
+        result.elapsed.as_nanos(),
+        result.outcome.is_ok(),
+        csv_field(error),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_record() {
+        let result = PartResult {
+            day: 17,
+            part: 1,
+            outcome: Ok(Answer::Text("4,6,3".to_string())),
+            parse_elapsed: Duration::from_nanos(300),
+            elapsed: Duration::from_nanos(1500),
+            verdict: Verdict::Pass,
+        };
+        assert_eq!(
+            json_record(&result),
+            r#"{"day": 17, "part": 1, "answer": "4,6,3", "type": "text", "status": "pass", "expected": null, "parse_ns": 300, "elapsed_ns": 1500, "success": true, "error": null}"#
+        );
+        let result = PartResult {
+            day: 5,
+            part: 2,
+            outcome: Err("We didn't find all the pages ! A \"cycle\"".to_string()),
+            parse_elapsed: Duration::from_nanos(21),
+            elapsed: Duration::from_nanos(42),
+            verdict: Verdict::Unknown,
+        };
+        assert_eq!(
+            json_record(&result),
+            r#"{"day": 5, "part": 2, "answer": null, "type": null, "status": "failed", "expected": null, "parse_ns": 21, "elapsed_ns": 42, "success": false, "error": "We didn't find all the pages ! A \"cycle\""}"#
+        );
+    }
+
+    #[test]
+    fn test_csv_record() {
+        let result = PartResult {
+            day: 1,
+            part: 2,
+            outcome: Ok(Answer::Integer(31)),
+            parse_elapsed: Duration::from_nanos(3),
+            elapsed: Duration::from_nanos(7),
+            verdict: Verdict::Fail(Answer::Integer(32)),
+        };
+        assert_eq!(csv_record(&result), "1,2,31,integer,wrong,32,3,7,true,");
+        let result = PartResult {
+            day: 17,
+            part: 1,
+            outcome: Ok(Answer::Text("4,6,3".to_string())),
+            parse_elapsed: Duration::from_nanos(3),
+            elapsed: Duration::from_nanos(7),
+            verdict: Verdict::Unknown,
+        };
+        assert_eq!(
+            csv_record(&result),
+            "17,1,\"4,6,3\",text,unknown,,3,7,true,"
+        );
+    }
+}
@@ -0,0 +1,135 @@
+/*
+    Some days have values that aren't in the input: the size of the
+    memory space of day 18, the room of the robots of day 14… The puzzle
+    values are the defaults, and `--param` overrides them to run the
+    solvers on other sizes.
+
+    The values stay strings until the day asks for them with the type it
+    expects, so a value that doesn't parse fails the parts of that day.
+*/
+
+use std::{collections::BTreeMap, fmt::Display, str::FromStr};
+
+use crate::solution::SolveError;
+
+/// A parameter a day accepts, listed by the command line.
+#[derive(Debug, Clone, Copy)]
+pub struct Param {
+    pub name: &'static str,
+    pub help: &'static str,
+}
+
+/// The parameters given to a day, by name.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Params {
+    values: BTreeMap<String, String>,
+}
+
+impl Params {
+    pub fn set(&mut self, name: &str, value: &str) {
+        self.values.insert(name.to_string(), value.to_string());
+    }
+
+    /// The value of a parameter, `None` when it isn't given.
+    pub fn get<T>(&self, name: &str) -> Result<Option<T>, SolveError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        self.values
+            .get(name)
+            .map(|value| {
+                value.parse().map_err(|error| {
+                    SolveError::InvalidParameter(format!("{}={}, {}", name, value, error))
+                })
+            })
+            .transpose()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.values
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+}
+
+/// A `--param` argument: `[DAY.]NAME=VALUE`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParamOverride {
+    pub day: Option<u8>,
+    pub name: String,
+    pub value: String,
+}
+
+impl FromStr for ParamOverride {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, value) = s
+            .split_once('=')
+            .ok_or_else(|| format!("`{}` is not NAME=VALUE", s))?;
+        let (day, name) = match name.split_once('.') {
+            Some((day, name)) => (
+                Some(
+                    day.parse::<u8>()
+                        .map_err(|_| format!("`{}` is not a day number", day))?,
+                ),
+                name,
+            ),
+            None => (None, name),
+        };
+        if name.is_empty() {
+            return Err(format!("`{}` has an empty name", s));
+        }
+        Ok(ParamOverride {
+            day,
+            name: name.to_string(),
+            value: value.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_param_override() {
+        assert_eq!(
+            "18.size=200x200".parse(),
+            Ok(ParamOverride {
+                day: Some(18),
+                name: "size".to_string(),
+                value: "200x200".to_string(),
+            })
+        );
+        assert_eq!(
+            "seconds=100".parse(),
+            Ok(ParamOverride {
+                day: None,
+                name: "seconds".to_string(),
+                value: "100".to_string(),
+            })
+        );
+        assert!("a.size=1".parse::<ParamOverride>().is_err());
+        assert!("size".parse::<ParamOverride>().is_err());
+        assert!("=1".parse::<ParamOverride>().is_err());
+    }
+
+    #[test]
+    fn test_params() {
+        let mut params = Params::default();
+        params.set("bytes", "1024");
+        params.set("size", "big");
+        assert_eq!(params.get::<usize>("bytes"), Ok(Some(1024)));
+        assert_eq!(params.get::<usize>("other"), Ok(None));
+        assert!(matches!(
+            params.get::<usize>("size"),
+            Err(SolveError::InvalidParameter(_))
+        ));
+        assert_eq!(
+            params.iter().collect::<Vec<_>>(),
+            vec![("bytes", "1024"), ("size", "big")]
+        );
+    }
+}
@@ -0,0 +1,189 @@
+/*
+    Every day parses its input with nom, this module turns the nom errors
+    into something readable: the day, where in the input it failed and
+    the offending line. Input left after the parser is also an error,
+    apart from trailing whitespace, otherwise a parser stopping early
+    would silently solve only half of the input.
+*/
+
+use std::fmt::Display;
+
+use nom::IResult;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// The parser failed, with the nom error of the innermost parser.
+    Invalid(nom::error::ErrorKind),
+    /// The parser needed more input.
+    Incomplete,
+    /// The parser succeeded but didn't consume everything.
+    Unconsumed,
+    /// The input parsed but doesn't make sense, with why.
+    Malformed(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: u8,
+    /// Position of the error, both starting at 1.
+    pub line: usize,
+    pub column: usize,
+    /// The line of the input where the error is.
+    pub snippet: String,
+    pub kind: ParseErrorKind,
+}
+
+impl ParseError {
+    fn at(day: u8, data: &str, offset: usize, kind: ParseErrorKind) -> Self {
+        let before = &data[..offset];
+        let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+        let line_end = data[offset..]
+            .find('\n')
+            .map_or(data.len(), |index| offset + index);
+        ParseError {
+            day,
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            snippet: data[line_start..line_end]
+                .trim_end_matches('\r')
+                .to_string(),
+            kind,
+        }
+    }
+
+    /// An error at the start of `part`, a slice of `data`, for the inputs
+    /// that parse fine but don't hold what the day expects.
+    pub fn malformed(day: u8, data: &str, part: &str, reason: impl Into<String>) -> Self {
+        let offset = (part.as_ptr() as usize)
+            .checked_sub(data.as_ptr() as usize)
+            .filter(|offset| *offset <= data.len())
+            .expect("The part is not in the data");
+        ParseError::at(day, data, offset, ParseErrorKind::Malformed(reason.into()))
+    }
+
+    fn description(&self) -> String {
+        match &self.kind {
+            ParseErrorKind::Invalid(kind) => format!("invalid input ({})", kind.description()),
+            ParseErrorKind::Incomplete => "incomplete input".to_string(),
+            ParseErrorKind::Unconsumed => "unexpected input after the end".to_string(),
+            ParseErrorKind::Malformed(reason) => format!("malformed input, {}", reason),
+        }
+    }
+
+    /// A multi-line report pointing at the error in the offending line.
+    pub fn report(&self) -> String {
+        let line_number = self.line.to_string();
+        let margin = " ".repeat(line_number.len());
+        format!(
+            "day {:02}: {} at line {}, column {}\n{} |\n{} | {}\n{} | {}^",
+            self.day,
+            self.description(),
+            self.line,
+            self.column,
+            margin,
+            line_number,
+            self.snippet,
+            margin,
+            " ".repeat(self.column - 1),
+        )
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{} at line {}, column {}: `{}`",
+            self.description(),
+            self.line,
+            self.column,
+            self.snippet
+        )
+    }
+}
+
+/// Runs the parser of a day on the whole input, only trailing whitespace
+/// may be left.
+pub fn parse_all<'a, T>(
+    day: u8,
+    data: &'a str,
+    mut parser: impl FnMut(&'a str) -> IResult<&'a str, T>,
+) -> Result<T, ParseError> {
+    match parser(data) {
+        Ok((rest, value)) => {
+            let rest = rest.trim_start();
+            if rest.is_empty() {
+                Ok(value)
+            } else {
+                Err(ParseError::at(
+                    day,
+                    data,
+                    data.len() - rest.len(),
+                    ParseErrorKind::Unconsumed,
+                ))
+            }
+        }
+        Err(nom::Err::Error(error)) | Err(nom::Err::Failure(error)) => Err(ParseError::at(
+            day,
+            data,
+            data.len() - error.input.len(),
+            ParseErrorKind::Invalid(error.code),
+        )),
+        Err(nom::Err::Incomplete(_)) => Err(ParseError::at(
+            day,
+            data,
+            data.len(),
+            ParseErrorKind::Incomplete,
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nom::{bytes::complete::tag, character::complete::line_ending, multi::separated_list1};
+
+    fn parse_numbers(data: &str) -> IResult<&str, Vec<u32>> {
+        separated_list1(line_ending, nom::character::complete::u32)(data)
+    }
+
+    #[test]
+    fn test_parse_all() {
+        assert_eq!(parse_all(1, "1\n2\n3\n", parse_numbers), Ok(vec![1, 2, 3]));
+
+        let error = parse_all(1, "1\n2\n3x\n4", parse_numbers).unwrap_err();
+        assert_eq!(error.kind, ParseErrorKind::Unconsumed);
+        assert_eq!((error.line, error.column), (3, 2));
+        assert_eq!(error.snippet, "3x");
+        assert_eq!(
+            error.report(),
+            "day 01: unexpected input after the end at line 3, column 2\n  |\n3 | 3x\n  |  ^"
+        );
+
+        let error = parse_all(17, "Register B: 1", tag("Register A: ")).unwrap_err();
+        assert_eq!(
+            error.kind,
+            ParseErrorKind::Invalid(nom::error::ErrorKind::Tag)
+        );
+        assert_eq!((error.line, error.column), (1, 1));
+        assert_eq!(
+            error.to_string(),
+            "invalid input (Tag) at line 1, column 1: `Register B: 1`"
+        );
+    }
+
+    #[test]
+    fn test_malformed() {
+        let data = "#.#\n.#.";
+        let error = ParseError::malformed(25, data, &data[5..], "a hole");
+        assert_eq!((error.line, error.column), (2, 2));
+        assert_eq!(error.kind, ParseErrorKind::Malformed("a hole".to_string()));
+    }
+
+    #[test]
+    #[should_panic(expected = "The part is not in the data")]
+    fn test_malformed_outside() {
+        let data = "#.#\n.#.";
+        ParseError::malformed(25, &data[4..], &data[..3], "before the data");
+    }
+}
@@ -0,0 +1,301 @@
+/*
+    The searches I wrote again and again with a `BinaryHeap<Reverse<…>>`,
+    once and for all. The states, their successors with the cost of the
+    step, the heuristic and the goal are all given by the caller, so it
+    works on a grid as well as on a grid with a direction, or on anything
+    hashable.
+
+    The A* expects a heuristic that never overestimates the remaining cost,
+    the Manhattan distance on a grid is fine. Dijkstra is an A* with a
+    heuristic of zero, and the BFS is there for when every step costs 1.
+
+    `shortest_path_nodes` is what day 16 part 2 needed: every node that is
+    on at least one of the shortest paths. It keeps all the parents that
+    reach a node with its best cost, and walks them back from the goals.
+*/
+
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap, HashSet, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+
+/// A cost of the searches: zero is the default value, and costs add up.
+pub trait Cost: Copy + Ord + Add<Output = Self> + Default {}
+
+impl<T: Copy + Ord + Add<Output = T> + Default> Cost for T {}
+
+/// A node waiting in the priority queue, the lowest estimate comes out first.
+struct Entry<N, C> {
+    estimate: C,
+    cost: C,
+    node: N,
+}
+
+impl<N, C: Ord> PartialEq for Entry<N, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<N, C: Ord> Eq for Entry<N, C> {}
+
+impl<N, C: Ord> PartialOrd for Entry<N, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N, C: Ord> Ord for Entry<N, C> {
+    // Reversed, the BinaryHeap returns the greatest element
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .estimate
+            .cmp(&self.estimate)
+            .then_with(|| other.cost.cmp(&self.cost))
+    }
+}
+
+/// The path from the start to `end`, following the parent of each node.
+pub fn reconstruct_path<N: Clone + Eq + Hash>(parents: &HashMap<N, N>, end: N) -> Vec<N> {
+    let mut path = vec![end];
+    while let Some(parent) = parents.get(path.last().expect("The path is never empty")) {
+        path.push(parent.clone());
+    }
+    path.reverse();
+    path
+}
+
+/// The shortest path from `start` to a node accepted by `success`, start and
+/// end included, with its cost. `successors` gives the next nodes and the
+/// cost to reach them, `heuristic` estimates the cost left to the goal.
+pub fn astar<N, C, FN, IN, FH, FS>(
+    start: N,
+    mut successors: FN,
+    mut heuristic: FH,
+    mut success: FS,
+) -> Option<(Vec<N>, C)>
+where
+    N: Clone + Eq + Hash,
+    C: Cost,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = (N, C)>,
+    FH: FnMut(&N) -> C,
+    FS: FnMut(&N) -> bool,
+{
+    let mut costs: HashMap<N, C> = HashMap::new();
+    let mut parents: HashMap<N, N> = HashMap::new();
+    let mut priority_queue = BinaryHeap::new();
+
+    costs.insert(start.clone(), C::default());
+    priority_queue.push(Entry {
+        estimate: heuristic(&start),
+        cost: C::default(),
+        node: start,
+    });
+
+    while let Some(Entry { cost, node, .. }) = priority_queue.pop() {
+        if success(&node) {
+            return Some((reconstruct_path(&parents, node), cost));
+        }
+
+        // a better path to this node was found after this one was queued
+        if costs[&node] < cost {
+            continue;
+        }
+
+        for (next, step_cost) in successors(&node) {
+            let next_cost = cost + step_cost;
+            if let Some(&known_cost) = costs.get(&next) {
+                if known_cost <= next_cost {
+                    continue;
+                }
+            }
+            costs.insert(next.clone(), next_cost);
+            parents.insert(next.clone(), node.clone());
+            priority_queue.push(Entry {
+                estimate: next_cost + heuristic(&next),
+                cost: next_cost,
+                node: next,
+            });
+        }
+    }
+
+    None
+}
+
+/// Like `astar`, without a heuristic.
+pub fn dijkstra<N, C, FN, IN, FS>(start: N, successors: FN, success: FS) -> Option<(Vec<N>, C)>
+where
+    N: Clone + Eq + Hash,
+    C: Cost,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = (N, C)>,
+    FS: FnMut(&N) -> bool,
+{
+    astar(start, successors, |_| C::default(), success)
+}
+
+/// The shortest path when every step costs 1, start and end included.
+pub fn bfs<N, FN, IN, FS>(start: N, mut successors: FN, mut success: FS) -> Option<Vec<N>>
+where
+    N: Clone + Eq + Hash,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = N>,
+    FS: FnMut(&N) -> bool,
+{
+    let mut parents: HashMap<N, N> = HashMap::new();
+    let mut seen: HashSet<N> = HashSet::from([start.clone()]);
+    let mut queue = VecDeque::from([start]);
+
+    while let Some(node) = queue.pop_front() {
+        if success(&node) {
+            return Some(reconstruct_path(&parents, node));
+        }
+        for next in successors(&node) {
+            if seen.insert(next.clone()) {
+                parents.insert(next.clone(), node.clone());
+                queue.push_back(next);
+            }
+        }
+    }
+
+    None
+}
+
+/// Every node that is on at least one shortest path from `start` to a node
+/// accepted by `success`, with the cost of those paths.
+pub fn shortest_path_nodes<N, C, FN, IN, FS>(
+    start: N,
+    mut successors: FN,
+    mut success: FS,
+) -> Option<(HashSet<N>, C)>
+where
+    N: Clone + Eq + Hash,
+    C: Cost,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = (N, C)>,
+    FS: FnMut(&N) -> bool,
+{
+    let mut costs: HashMap<N, C> = HashMap::new();
+    let mut parents: HashMap<N, Vec<N>> = HashMap::new();
+    let mut priority_queue = BinaryHeap::new();
+    let mut best_cost: Option<C> = None;
+    let mut ends: Vec<N> = Vec::new();
+
+    costs.insert(start.clone(), C::default());
+    priority_queue.push(Entry {
+        estimate: C::default(),
+        cost: C::default(),
+        node: start,
+    });
+
+    while let Some(Entry { cost, node, .. }) = priority_queue.pop() {
+        // every path left is longer than the best ones
+        if best_cost.is_some_and(|best_cost| cost > best_cost) {
+            break;
+        }
+        if costs[&node] < cost {
+            continue;
+        }
+        if success(&node) {
+            // other goals may be reached with the same cost, keep going
+            best_cost = Some(cost);
+            ends.push(node);
+            continue;
+        }
+
+        for (next, step_cost) in successors(&node) {
+            let next_cost = cost + step_cost;
+            match costs.get(&next) {
+                Some(&known_cost) if known_cost < next_cost => {}
+                Some(&known_cost) if known_cost == next_cost => {
+                    parents.entry(next).or_default().push(node.clone());
+                }
+                _ => {
+                    costs.insert(next.clone(), next_cost);
+                    parents.insert(next.clone(), vec![node.clone()]);
+                    priority_queue.push(Entry {
+                        estimate: next_cost,
+                        cost: next_cost,
+                        node: next,
+                    });
+                }
+            }
+        }
+    }
+
+    let best_cost = best_cost?;
+    let mut nodes: HashSet<N> = HashSet::new();
+    while let Some(node) = ends.pop() {
+        if nodes.insert(node.clone()) {
+            if let Some(node_parents) = parents.get(&node) {
+                ends.extend(node_parents.iter().cloned());
+            }
+        }
+    }
+    Some((nodes, best_cost))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0 -1-> 1 -1-> 3
+    // 0 -2-> 2 -0-> 3
+    // 0 -5-> 3 -1-> 4
+    fn successors(node: &u32) -> Vec<(u32, u32)> {
+        match node {
+            0 => vec![(1, 1), (2, 2), (3, 5)],
+            1 => vec![(3, 1)],
+            2 => vec![(3, 0)],
+            3 => vec![(4, 1)],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn test_dijkstra_and_astar() {
+        assert_eq!(
+            dijkstra(0, successors, |&node| node == 4),
+            Some((vec![0, 1, 3, 4], 3))
+        );
+        assert_eq!(
+            astar(
+                0,
+                successors,
+                |&node| u32::from(node == 0),
+                |&node| node == 3
+            ),
+            Some((vec![0, 1, 3], 2))
+        );
+        assert_eq!(dijkstra(3, successors, |&node| node == 0), None);
+    }
+
+    #[test]
+    fn test_bfs() {
+        let neighbors = |node: &u32| successors(node).into_iter().map(|(next, _)| next);
+        assert_eq!(bfs(0, neighbors, |&node| node == 4), Some(vec![0, 3, 4]));
+        assert_eq!(bfs(4, neighbors, |&node| node == 0), None);
+    }
+
+    #[test]
+    fn test_shortest_path_nodes() {
+        assert_eq!(
+            shortest_path_nodes(0, successors, |&node| node == 4),
+            Some((HashSet::from([0, 1, 2, 3, 4]), 3))
+        );
+        assert_eq!(
+            shortest_path_nodes(0, successors, |&node| node == 1 || node == 2),
+            Some((HashSet::from([0, 1]), 1))
+        );
+    }
+
+    #[test]
+    fn test_reconstruct_path() {
+        let parents = HashMap::from([(2, 1), (1, 0)]);
+        assert_eq!(reconstruct_path(&parents, 2), vec![0, 1, 2]);
+        assert_eq!(reconstruct_path(&parents, 0), vec![0]);
+    }
+}
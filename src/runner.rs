@@ -0,0 +1,106 @@
+/*
+    Runs the days of the registry, each day parses its input once and
+    the parts are timed on their own. A part that can't be solved fails
+    with its error, and a panic is caught and reported the same way, so
+    a broken day doesn't stop the others. An input
+    that doesn't parse fails the parts too, with the parse error kept
+    aside to be reported in full.
+*/
+
+use std::{
+    any::Any,
+    panic::AssertUnwindSafe,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    answers::Verdict, output::PartResult, params::Params, parsing::ParseError,
+    solution::DynSolution,
+};
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "panicked".to_string()
+    }
+}
+
+/// Runs `f`, and returns the message of its panic if it panics. The
+/// default hook is silenced meanwhile, as the panic is reported with the
+/// results, and restored afterwards so the panics elsewhere are still
+/// printed.
+pub fn catch_panic<T>(f: impl FnOnce() -> T) -> Result<T, String> {
+    let hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(|_| {}));
+    let result = std::panic::catch_unwind(AssertUnwindSafe(f));
+    std::panic::set_hook(hook);
+    result.map_err(|payload| panic_message(payload.as_ref()))
+}
+
+fn execute_part(
+    day: &dyn DynSolution,
+    part: u8,
+    input: &dyn Any,
+    params: &Params,
+    parse_elapsed: Duration,
+) -> PartResult {
+    let now = Instant::now();
+    // A panicking day should not prevent the other days from running.
+    let outcome = match catch_panic(|| day.solve(part, input, params)) {
+        Ok(answer) => answer.map_err(|error| error.to_string()),
+        Err(message) => Err(format!("panicked: {}", message)),
+    };
+    PartResult {
+        day: day.day(),
+        part,
+        outcome,
+        parse_elapsed,
+        elapsed: now.elapsed(),
+        verdict: Verdict::Unknown,
+    }
+}
+
+pub struct DayResults {
+    pub parts: Vec<PartResult>,
+    /// Why the input could not be parsed, if it couldn't.
+    pub parse_error: Option<ParseError>,
+}
+
+/// Parses the input once and gives it to the selected parts. When the
+/// parsing fails, the selected parts fail with the same message.
+pub fn execute_day(day: &dyn DynSolution, data: &str, parts: &[u8], params: &Params) -> DayResults {
+    let now = Instant::now();
+    let input = catch_panic(|| day.parse(data));
+    let parse_elapsed = now.elapsed();
+
+    let (message, parse_error) = match input {
+        Ok(Ok(input)) => {
+            return DayResults {
+                parts: parts
+                    .iter()
+                    .map(|number| execute_part(day, *number, input.as_ref(), params, parse_elapsed))
+                    .collect(),
+                parse_error: None,
+            }
+        }
+        Ok(Err(error)) => (format!("parse error: {}", error), Some(error)),
+        Err(message) => (format!("parsing failed: {}", message), None),
+    };
+    DayResults {
+        parts: parts
+            .iter()
+            .map(|&number| PartResult {
+                day: day.day(),
+                part: number,
+                outcome: Err(message.clone()),
+                parse_elapsed,
+                elapsed: Duration::ZERO,
+                verdict: Verdict::Unknown,
+            })
+            .collect(),
+        parse_error,
+    }
+}
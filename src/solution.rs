@@ -0,0 +1,264 @@
+/*
+    Every day implements the same `Solution` trait, and the registry lists
+    them all, so the runner, the benchmarks and the tests can go through
+    the days without knowing how each one parses or what it returns.
+*/
+
+use std::{any::Any, fmt::Display};
+
+use crate::{
+    answer::Answer,
+    answers::Verdict,
+    params::{Param, Params},
+    parsing::ParseError,
+};
+
+/// A puzzle example with its known answers, a part without an answer
+/// is not checked (some examples only make sense for one part). The
+/// parameters are for the examples that don't use the puzzle values,
+/// like a smaller room.
+#[derive(Debug, Clone, Copy)]
+pub struct Example {
+    pub data: &'static str,
+    pub part_1: Option<&'static str>,
+    pub part_2: Option<&'static str>,
+    pub params: &'static [(&'static str, &'static str)],
+}
+
+impl Example {
+    /// The parameters of the example, then the `overrides` on top of them.
+    pub fn params(&self, overrides: &Params) -> Params {
+        let mut params = Params::default();
+        for (name, value) in self.params {
+            params.set(name, value);
+        }
+        for (name, value) in overrides.iter() {
+            params.set(name, value);
+        }
+        params
+    }
+
+    pub fn answer(&self, part: u8) -> Option<&'static str> {
+        if part == 1 {
+            self.part_1
+        } else {
+            self.part_2
+        }
+    }
+
+    pub fn verify(&self, part: u8, answer: &Answer) -> Verdict {
+        match self.answer(part) {
+            Some(expected) if expected == answer.to_string() => Verdict::Pass,
+            Some(expected) => Verdict::Fail(Answer::Text(expected.to_string())),
+            None => Verdict::Unknown,
+        }
+    }
+}
+
+/// Why a part could not be solved, the input parsed fine but doesn't
+/// hold what the solution expects.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolveError {
+    /// A cell the puzzle relies on, like the start or the exit, isn't in the map.
+    MissingCell(&'static str),
+    /// There is no path between the start and the exit.
+    NoPath,
+    /// The chronospatial computer met an opcode it doesn't know.
+    InvalidOpcode { opcode: u8, position: usize },
+    /// An operand that is reserved or out of range.
+    InvalidOperand { operand: u8, position: usize },
+    /// The program didn't halt within the given number of instructions.
+    InfiniteLoop { instructions: u64 },
+    /// A gate of the circuit that can't be evaluated.
+    InvalidGate(String),
+    /// The input breaks an assumption the solution is built on.
+    Unsupported(String),
+    /// A `--param` value the day can't use.
+    InvalidParameter(String),
+    /// Everything was searched and nothing fits.
+    NoSolution,
+}
+
+impl Display for SolveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            SolveError::MissingCell(cell) => write!(f, "no {} cell in the map", cell),
+            SolveError::NoPath => write!(f, "no path from the start to the exit"),
+            SolveError::InvalidOpcode { opcode, position } => {
+                write!(f, "invalid opcode {} at position {}", opcode, position)
+            }
+            SolveError::InvalidOperand { operand, position } => {
+                write!(f, "invalid operand {} at position {}", operand, position)
+            }
+            SolveError::InfiniteLoop { instructions } => write!(
+                f,
+                "still running after {} instructions, probably an infinite loop",
+                instructions
+            ),
+            SolveError::InvalidGate(gate) => write!(f, "invalid gate {}", gate),
+            SolveError::Unsupported(reason) => write!(f, "unsupported input: {}", reason),
+            SolveError::InvalidParameter(reason) => write!(f, "invalid parameter {}", reason),
+            SolveError::NoSolution => write!(f, "no solution found"),
+        }
+    }
+}
+
+pub trait Solution: Sync {
+    /// The parsed input, shared by both parts.
+    type Input: 'static;
+
+    fn day(&self) -> u8;
+
+    /// The title of the puzzle.
+    fn name(&self) -> &'static str;
+
+    fn parse(&self, data: &str) -> Result<Self::Input, ParseError>;
+
+    fn part_1(&self, input: &Self::Input) -> Result<Answer, SolveError>;
+
+    fn part_2(&self, input: &Self::Input) -> Result<Answer, SolveError>;
+
+    fn examples(&self) -> &'static [Example] {
+        &[]
+    }
+
+    /// The parameters the day accepts beside its input.
+    fn params(&self) -> &'static [Param] {
+        &[]
+    }
+
+    /// Solves a part with the given parameters, the days without
+    /// parameters only implement the parts.
+    fn solve_with(
+        &self,
+        part: u8,
+        input: &Self::Input,
+        _params: &Params,
+    ) -> Result<Answer, SolveError> {
+        match part {
+            1 => self.part_1(input),
+            2 => self.part_2(input),
+            _ => panic!("There is no part {}", part),
+        }
+    }
+}
+
+/// The object safe version of `Solution`, the parsed input is type-erased
+/// so that every day fits in the same registry.
+pub trait DynSolution: Sync {
+    fn day(&self) -> u8;
+
+    fn name(&self) -> &'static str;
+
+    fn parse(&self, data: &str) -> Result<Box<dyn Any>, ParseError>;
+
+    /// Solves a part on an input returned by `parse`.
+    fn solve(&self, part: u8, input: &dyn Any, params: &Params) -> Result<Answer, SolveError>;
+
+    fn examples(&self) -> &'static [Example];
+
+    fn params(&self) -> &'static [Param];
+}
+
+impl<S: Solution> DynSolution for S {
+    fn day(&self) -> u8 {
+        Solution::day(self)
+    }
+
+    fn name(&self) -> &'static str {
+        Solution::name(self)
+    }
+
+    fn parse(&self, data: &str) -> Result<Box<dyn Any>, ParseError> {
+        Ok(Box::new(Solution::parse(self, data)?))
+    }
+
+    fn solve(&self, part: u8, input: &dyn Any, params: &Params) -> Result<Answer, SolveError> {
+        let input = input
+            .downcast_ref::<S::Input>()
+            .expect("The parsed input does not match the day");
+        self.solve_with(part, input, params)
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        Solution::examples(self)
+    }
+
+    fn params(&self) -> &'static [Param] {
+        Solution::params(self)
+    }
+}
+
+pub static SOLUTIONS: &[&dyn DynSolution] = &[
+    &crate::day_01::Day01,
+    &crate::day_02::Day02,
+    &crate::day_03::Day03,
+    &crate::day_04::Day04,
+    &crate::day_05::Day05,
+    &crate::day_06::Day06,
+    &crate::day_07::Day07,
+    &crate::day_08::Day08,
+    &crate::day_09::Day09,
+    &crate::day_10::Day10,
+    &crate::day_11::Day11,
+    &crate::day_12::Day12,
+    &crate::day_13::Day13,
+    &crate::day_14::Day14,
+    &crate::day_15::Day15,
+    &crate::day_16::Day16,
+    &crate::day_17::Day17,
+    &crate::day_18::Day18,
+    &crate::day_19::Day19,
+    &crate::day_20::Day20,
+    &crate::day_21::Day21,
+    &crate::day_22::Day22,
+    &crate::day_23::Day23,
+    &crate::day_24::Day24,
+    &crate::day_25::Day25,
+];
+
+pub fn find(day: u8) -> Option<&'static dyn DynSolution> {
+    SOLUTIONS
+        .iter()
+        .find(|solution| solution.day() == day)
+        .copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_is_sorted() {
+        let days = SOLUTIONS
+            .iter()
+            .map(|solution| solution.day())
+            .collect::<Vec<_>>();
+        assert_eq!(days, (1..=25).collect::<Vec<_>>());
+        assert_eq!(find(17).unwrap().name(), "Chronospatial Computer");
+        assert!(find(26).is_none());
+    }
+
+    #[test]
+    fn test_registry_examples() {
+        for solution in SOLUTIONS {
+            for example in solution.examples() {
+                let input = solution.parse(example.data).unwrap();
+                let params = example.params(&Params::default());
+                // Some parts can't run on the example of the other part.
+                for part in [1, 2] {
+                    if example.answer(part).is_some() {
+                        let answer = solution.solve(part, input.as_ref(), &params).unwrap();
+                        assert_eq!(
+                            example.verify(part, &answer),
+                            Verdict::Pass,
+                            "day {} part {}",
+                            solution.day(),
+                            part
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,28 +1,87 @@
 /*
     Comments.
+
+    Copy as src/day_NN.rs, add `pub mod day_NN;` to src/lib.rs and
+    `&crate::day_NN::DayNN` to the `SOLUTIONS` registry of src/solution.rs.
 */
 
-pub fn day_N_part_1(data: &str) -> i64 {
+use nom::{
+    character::complete::{line_ending, not_line_ending},
+    combinator::map,
+    multi::separated_list0,
+    IResult,
+};
+
+use crate::{
+    answer::Answer,
+    parsing::{parse_all, ParseError},
+    solution::{Example, Solution, SolveError},
+};
+
+fn parse_input_data(data: &str) -> IResult<&str, Vec<String>> {
+    separated_list0(line_ending, map(not_line_ending, str::to_string))(data)
+}
+
+pub fn day_NN_parse(data: &str) -> Result<Vec<String>, ParseError> {
+    parse_all(NN, data, parse_input_data)
+}
+
+pub fn day_NN_part_1(data: &[String]) -> i64 {
     42
 }
 
-pub fn day_N_part_2(data: &str) -> i64 {
+pub fn day_NN_part_2(data: &[String]) -> i64 {
     42
 }
 
+const EXAMPLE: &str = "EXAMPLE";
+
+pub struct DayNN;
+
+impl Solution for DayNN {
+    type Input = Vec<String>;
+
+    fn day(&self) -> u8 {
+        NN
+    }
+
+    fn name(&self) -> &'static str {
+        "TITLE"
+    }
+
+    fn parse(&self, data: &str) -> Result<Self::Input, ParseError> {
+        day_NN_parse(data)
+    }
+
+    fn part_1(&self, input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(day_NN_part_1(input).into())
+    }
+
+    fn part_2(&self, input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(day_NN_part_2(input).into())
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            data: EXAMPLE,
+            part_1: Some("42"),
+            part_2: Some("42"),
+            params: &[],
+        }]
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const EXAMPLE: &str = "EXAMPLE";
-
     #[test]
-    fn test_day_N_part_1() {
-        assert_eq!(day_N_part_1(EXAMPLE), 42);
+    fn test_day_NN_part_1() {
+        assert_eq!(day_NN_part_1(&day_NN_parse(EXAMPLE).unwrap()), 42);
     }
 
     #[test]
-    fn test_day_N_part_2() {
-        assert_eq!(day_N_part_2(EXAMPLE), 42);
+    fn test_day_NN_part_2() {
+        assert_eq!(day_NN_part_2(&day_NN_parse(EXAMPLE).unwrap()), 42);
     }
 }